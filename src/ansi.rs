@@ -0,0 +1,103 @@
+//! Just enough ANSI styling for coloured terminal reports.
+
+use std::{fmt::Display, io::IsTerminal};
+
+use anyhow::{bail, Result};
+
+use crate::Options;
+
+/// An SGR style, e.g. `"1;31"` for bold red.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style(pub &'static str);
+
+impl Style {
+    pub const RED: Style = Style("31");
+    pub const GREEN: Style = Style("32");
+    pub const YELLOW: Style = Style("33");
+    pub const BLUE: Style = Style("34");
+    pub const MAGENTA: Style = Style("35");
+    pub const CYAN: Style = Style("36");
+    pub const BOLD: Style = Style("1");
+    pub const DIM: Style = Style("2");
+    pub const STRIKE: Style = Style("9");
+
+    /// Distinct colours, for telling several things apart
+    pub const PALETTE: [Style; 6] = [
+        Style::RED,
+        Style::GREEN,
+        Style::YELLOW,
+        Style::BLUE,
+        Style::MAGENTA,
+        Style::CYAN,
+    ];
+
+    pub fn paint(self, text: impl Display) -> String {
+        format!("\x1b[{}m{text}\x1b[0m", self.0)
+    }
+
+    /// The same style as a CSS declaration, for HTML reports.
+    pub fn css(self) -> &'static str {
+        match self {
+            Style::RED => "color: #d70000",
+            Style::GREEN => "color: #00875f",
+            Style::YELLOW => "color: #af8700",
+            Style::BLUE => "color: #005fd7",
+            Style::MAGENTA => "color: #af00af",
+            Style::CYAN => "color: #008787",
+            Style::BOLD => "font-weight: bold",
+            Style::DIM => "opacity: 0.4",
+            Style::STRIKE => "text-decoration: line-through",
+            _ => "",
+        }
+    }
+}
+
+/// Whether to colour output, from the `color` option: `always`, `never` or
+/// `auto` (the default), which colours only when stdout is a terminal.
+pub fn enabled(options: &Options) -> Result<bool> {
+    match options.get_or("color", "auto".to_owned())?.as_str() {
+        "always" => Ok(true),
+        "never" => Ok(false),
+        "auto" => Ok(std::io::stdout().is_terminal()),
+        other => bail!("option `color`: expected `always`, `never` or `auto` (got `{other}`)"),
+    }
+}
+
+/// `text` without any escape sequences.
+pub fn strip(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("\x1b[") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        rest = rest.find('m').map_or("", |end| &rest[end + 1..]);
+    }
+    out + rest
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_paint() {
+        let red = Style::RED.paint(7);
+        assert_eq!(red, "\x1b[31m7\x1b[0m");
+        assert_eq!(strip(&format!("a {red} b")), "a 7 b");
+    }
+
+    #[test]
+    fn test_css() {
+        assert_eq!(Style::BOLD.css(), "font-weight: bold");
+        assert!(Style::PALETTE
+            .iter()
+            .all(|style| style.css().starts_with("color")));
+    }
+
+    #[test]
+    fn test_enabled() {
+        assert!(enabled(&Options::new().with("color", "always")).unwrap());
+        assert!(!enabled(&Options::new().with("color", "never")).unwrap());
+        assert!(enabled(&Options::new().with("color", "sometimes")).is_err());
+    }
+}
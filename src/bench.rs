@@ -0,0 +1,282 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Wraps the system allocator and counts every allocation.
+///
+/// Only has an effect when installed with `#[global_allocator]`; otherwise
+/// every phase reports zero allocations.
+pub struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+fn allocation_counters() -> (u64, u64) {
+    (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part1 => write!(f, "part 1"),
+            Phase::Part2 => write!(f, "part 2"),
+        }
+    }
+}
+
+/// Timings for one phase over every iteration. Durations are in nanoseconds
+/// so the saved JSON stays readable by other tools.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub phase: Phase,
+    pub iterations: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub p95_ns: u64,
+    /// Allocations per iteration, averaged over every iteration
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+impl PhaseStats {
+    pub fn median(&self) -> Duration {
+        Duration::from_nanos(self.median_ns)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayBench {
+    pub day: u32,
+    pub phases: Vec<PhaseStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub days: Vec<DayBench>,
+}
+
+/// Runs `f` `iterations` times and summarises how long it took. The value
+/// `f` returns is dropped outside the timed region.
+pub fn measure<T>(phase: Phase, iterations: usize, mut f: impl FnMut() -> T) -> PhaseStats {
+    let iterations = iterations.max(1);
+    let mut times = Vec::with_capacity(iterations);
+    let (allocations_before, bytes_before) = allocation_counters();
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let out = black_box(f());
+        times.push(start.elapsed().as_nanos() as u64);
+        drop(out);
+    }
+    let (allocations_after, bytes_after) = allocation_counters();
+
+    times.sort_unstable();
+    PhaseStats {
+        phase,
+        iterations,
+        min_ns: times[0],
+        median_ns: percentile(&times, 50),
+        p95_ns: percentile(&times, 95),
+        // the `times` buffer is allocated up front, so only `f` is counted
+        allocations: (allocations_after - allocations_before) / iterations as u64,
+        allocated_bytes: (bytes_after - bytes_before) / iterations as u64,
+    }
+}
+
+// nearest-rank percentile of an already sorted, non-empty slice
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    let rank = (p * sorted.len()).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub day: u32,
+    pub phase: Phase,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Regression {
+    pub fn slowdown(&self) -> f64 {
+        self.current.as_secs_f64() / self.baseline.as_secs_f64()
+    }
+}
+
+impl BenchReport {
+    pub fn load(path: &std::path::Path) -> anyhow::Result<BenchReport> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn phase(&self, day: u32, phase: Phase) -> Option<&PhaseStats> {
+        self.days
+            .iter()
+            .find(|bench| bench.day == day)?
+            .phases
+            .iter()
+            .find(|stats| stats.phase == phase)
+    }
+
+    /// Every phase whose median is more than `threshold` (e.g. `0.1` for 10%)
+    /// slower than in `baseline`. Phases missing from either report are
+    /// ignored.
+    pub fn regressions(&self, baseline: &BenchReport, threshold: f64) -> Vec<Regression> {
+        self.days
+            .iter()
+            .flat_map(|bench| bench.phases.iter().map(|stats| (bench.day, stats)))
+            .filter_map(|(day, stats)| {
+                let old = baseline.phase(day, stats.phase)?;
+                (stats.median_ns as f64 > old.median_ns as f64 * (1.0 + threshold)).then(|| {
+                    Regression {
+                        day,
+                        phase: stats.phase,
+                        baseline: old.median(),
+                        current: stats.median(),
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:>3} | {:>6} | {:>10} | {:>10} | {:>10} | {:>8} | {:>10}",
+            "day", "phase", "min", "median", "p95", "allocs", "bytes"
+        )?;
+        writeln!(f, "{}", "-".repeat(78))?;
+        for bench in &self.days {
+            for stats in &bench.phases {
+                writeln!(
+                    f,
+                    "{:>3} | {:>6} | {:>10} | {:>10} | {:>10} | {:>8} | {:>10}",
+                    bench.day,
+                    stats.phase.to_string(),
+                    format!("{:.2?}", Duration::from_nanos(stats.min_ns)),
+                    format!("{:.2?}", Duration::from_nanos(stats.median_ns)),
+                    format!("{:.2?}", Duration::from_nanos(stats.p95_ns)),
+                    stats.allocations,
+                    stats.allocated_bytes,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stats(phase: Phase, median_ns: u64) -> PhaseStats {
+        PhaseStats {
+            phase,
+            iterations: 1,
+            min_ns: median_ns,
+            median_ns,
+            p95_ns: median_ns,
+            allocations: 0,
+            allocated_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_percentile() {
+        let times = (1..=20).collect::<Vec<_>>();
+        assert_eq!(percentile(&times, 50), 10);
+        assert_eq!(percentile(&times, 95), 19);
+        assert_eq!(percentile(&[7], 95), 7);
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let stats = measure(Phase::Parse, 5, || calls += 1);
+        assert_eq!(calls, 5);
+        assert_eq!(stats.iterations, 5);
+        assert!(stats.min_ns <= stats.median_ns && stats.median_ns <= stats.p95_ns);
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = BenchReport {
+            days: vec![DayBench {
+                day: 6,
+                phases: vec![stats(Phase::Parse, 100), stats(Phase::Part2, 1000)],
+            }],
+        };
+        let current = BenchReport {
+            days: vec![
+                DayBench {
+                    day: 6,
+                    phases: vec![stats(Phase::Parse, 105), stats(Phase::Part2, 2000)],
+                },
+                DayBench {
+                    day: 9,
+                    phases: vec![stats(Phase::Part2, 5000)],
+                },
+            ],
+        };
+        let regressions = current.regressions(&baseline, 0.1);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(
+            (regressions[0].day, regressions[0].phase),
+            (6, Phase::Part2)
+        );
+        assert_eq!(regressions[0].slowdown(), 2.0);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let report = BenchReport {
+            days: vec![DayBench {
+                day: 1,
+                phases: vec![stats(Phase::Part1, 42)],
+            }],
+        };
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains(r#""phase":"part1""#));
+        assert_eq!(serde_json::from_str::<BenchReport>(&json).unwrap(), report);
+    }
+}
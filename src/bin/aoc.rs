@@ -0,0 +1,492 @@
+use std::{io::Read, path::PathBuf, str::FromStr};
+
+use anyhow::{anyhow, bail, Context, Result};
+use aoc_2024::{
+    bench::{BenchReport, CountingAlloc},
+    days,
+    input::{CacheMiss, HttpFetcher, InputProvider, DEFAULT_BASE_URL},
+    runner::{Record, Run},
+    submit::{self, HttpSubmitter, Ledger, Refusal},
+    Options, ParseError, Part,
+};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2024")]
+struct Cli {
+    #[command(flatten)]
+    inputs: InputArgs,
+    #[command(flatten)]
+    options: OptionArgs,
+    /// Also show what each part noted along the way (on stderr, or in the
+    /// records for json output)
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Where downloaded inputs are kept
+    #[arg(long, global = true, env = "AOC_CACHE_DIR", default_value = "inputs")]
+    cache_dir: PathBuf,
+    /// Site to download missing inputs from
+    #[arg(long, global = true, env = "AOC_BASE_URL", default_value = DEFAULT_BASE_URL)]
+    base_url: String,
+    /// Session cookie; without it, only cached inputs are used
+    #[arg(long, global = true, env = "AOC_SESSION", hide_env_values = true)]
+    session: Option<String>,
+}
+
+#[derive(Args)]
+struct OptionArgs {
+    /// TOML file of day-specific options, in `[dayN]` tables
+    #[arg(long, global = true, env = "AOC_CONFIG")]
+    config: Option<PathBuf>,
+    /// A day-specific option, e.g. `format=csv`; overrides the config file
+    /// and can be repeated
+    #[arg(
+        long = "set",
+        global = true,
+        value_name = "KEY=VALUE",
+        value_parser = Options::parse_pair
+    )]
+    options: Vec<(String, String)>,
+}
+
+impl OptionArgs {
+    fn for_day(&self, day: u32) -> Result<Options> {
+        let from_file = match &self.config {
+            Some(path) => Options::load(path, day)?,
+            None => Options::new(),
+        };
+        Ok(from_file.merge(self.options.iter().cloned().collect()))
+    }
+}
+
+impl InputArgs {
+    fn provider(&self) -> InputProvider {
+        let provider = InputProvider::new(&self.cache_dir);
+        match &self.session {
+            Some(session) => provider.with_fetcher(HttpFetcher::new(&self.base_url, session)),
+            None => provider,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one day (or every day) and print the answers
+    Run {
+        /// Day number, or `all`
+        target: Target,
+        /// Only run this part
+        #[arg(long, value_parser = parse_part)]
+        part: Option<Part>,
+        /// Read the puzzle input from this file, or `-` for stdin
+        #[arg(long)]
+        input: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Print a day's report on how its answers came about
+    Explain {
+        day: Target,
+        /// Read the puzzle input from this file, or `-` for stdin
+        #[arg(long)]
+        input: Option<PathBuf>,
+    },
+    /// Time parsing and both parts of one day (or every day)
+    Bench {
+        /// Day number, or `all`
+        target: Target,
+        /// How many times to repeat each phase
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+        /// Save the results as JSON
+        #[arg(long)]
+        save: Option<PathBuf>,
+        /// Compare against results saved by an earlier run
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Flag phases whose median is this many percent slower than the baseline
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Send an answer, unless earlier guesses already rule it out
+    Submit {
+        day: Target,
+        #[arg(long, value_parser = parse_part)]
+        part: Part,
+        /// The answer to send; worked out from the input if left out
+        answer: Option<String>,
+        /// Where every guess is recorded, one file per day
+        #[arg(long, env = "AOC_LEDGER_DIR", default_value = "answers")]
+        ledger_dir: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// `day.part: answer` lines, or a table for `all`
+    Text,
+    /// A single array of records
+    Json,
+    /// One record per line, as each day finishes
+    Ndjson,
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Day(u32),
+    All,
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(Target::All);
+        }
+        let day = s
+            .parse::<u32>()
+            .map_err(|_| format!("expected a day number or `all`, got `{s}`"))?;
+        days::get(day)
+            .map(|_| Target::Day(day))
+            .ok_or_else(|| format!("day {day} is not solved yet"))
+    }
+}
+
+fn parse_part(s: &str) -> Result<Part, String> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("expected 1 or 2, got `{s}`")),
+    }
+}
+
+fn read_input(path: &PathBuf) -> Result<String> {
+    if path.as_os_str() == "-" {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        return Ok(input);
+    }
+    std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+}
+
+// the provider refetches cached inputs it can't trust without a word; an
+// unreadable metadata file is worth a warning, as the cache may be damaged
+fn load_input(provider: &InputProvider, day: u32) -> Result<String> {
+    let (input, miss) = provider.load(day)?;
+    if let Some(miss @ CacheMiss::Corrupt(_)) = miss {
+        eprintln!("warning: fetched day {day} again: {miss}");
+    }
+    Ok(input)
+}
+
+fn print_table(runs: &[(u32, Result<Run>)]) {
+    println!(
+        "{:>3} | {:>16} | {:>16} | {:>10} | {:>10} | {:>10}",
+        "day", "part 1", "part 2", "parse", "part 1", "part 2"
+    );
+    println!("{}", "-".repeat(82));
+    for (day, run) in runs {
+        match run {
+            Ok(run) => {
+                let answer = |part| run.answer(part).map_or("", |a| a.answer.as_str());
+                let elapsed = |part| {
+                    run.answer(part)
+                        .map_or(String::new(), |a| format!("{:.2?}", a.elapsed))
+                };
+                println!(
+                    "{:>3} | {:>16} | {:>16} | {:>10} | {:>10} | {:>10}",
+                    day,
+                    answer(Part::One),
+                    answer(Part::Two),
+                    format!("{:.2?}", run.parse),
+                    elapsed(Part::One),
+                    elapsed(Part::Two),
+                );
+            }
+            // parse errors span several lines, so only their summary goes
+            // in the table
+            Err(e) => println!(
+                "{day:>3} | {}",
+                format!("{e:#}").lines().next().unwrap_or("")
+            ),
+        }
+    }
+    for (_, run) in runs {
+        if let Err(e) = run {
+            if e.is::<ParseError>() {
+                eprintln!("\n{e}");
+            }
+        }
+    }
+}
+
+fn print_diagnostics(run: &Run) {
+    for answer in &run.answers {
+        for note in &answer.diagnostics {
+            eprintln!("{}.{}: {note}", run.day, answer.part);
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Output<'a> {
+    Answer(Record<'a>),
+    Failed { day: u32, error: String },
+}
+
+fn outputs(day: u32, run: &Result<Run>, verbose: bool) -> Vec<Output<'_>> {
+    match run {
+        Ok(run) => run.records(verbose).map(Output::Answer).collect(),
+        Err(e) => vec![Output::Failed {
+            day,
+            error: format!("{e:#}"),
+        }],
+    }
+}
+
+/// Prints runs as json records, one per answer. Failed days get a record
+/// with just the day and the error. Returns whether any day failed.
+fn print_records(
+    runs: impl Iterator<Item = (u32, Result<Run>)>,
+    format: Format,
+    verbose: bool,
+) -> Result<bool> {
+    let mut failed = false;
+    if format == Format::Ndjson {
+        for (day, run) in runs {
+            failed |= run.is_err();
+            for output in outputs(day, &run, verbose) {
+                println!("{}", serde_json::to_string(&output)?);
+            }
+        }
+    } else {
+        let runs = runs.collect::<Vec<_>>();
+        failed = runs.iter().any(|(_, run)| run.is_err());
+        let all = runs
+            .iter()
+            .flat_map(|(day, run)| outputs(*day, run, verbose))
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&all)?);
+    }
+    Ok(failed)
+}
+
+fn bench(
+    provider: &InputProvider,
+    options: &OptionArgs,
+    target: Target,
+    iterations: usize,
+    save: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+    threshold: f64,
+) -> Result<()> {
+    let runners = match target {
+        Target::Day(day) => vec![days::get(day).ok_or_else(|| anyhow!("day {day} not found"))?],
+        Target::All => days::DAYS.to_vec(),
+    };
+
+    let mut report = BenchReport::default();
+    for runner in runners {
+        match load_input(provider, runner.day()) {
+            Ok(input) => match runner.bench(&input, &options.for_day(runner.day())?, iterations) {
+                Ok(bench) => report.days.push(bench),
+                Err(e) => eprintln!("skipping {e:#}"),
+            },
+            Err(e) => eprintln!("skipping day {}: {e:#}", runner.day()),
+        }
+    }
+    print!("{report}");
+
+    if let Some(path) = save {
+        report.save(&path)?;
+    }
+
+    if let Some(path) = baseline {
+        let baseline = BenchReport::load(&path)?;
+        let regressions = report.regressions(&baseline, threshold / 100.0);
+        if regressions.is_empty() {
+            println!("no regressions against {}", path.display());
+        }
+        for regression in regressions {
+            println!(
+                "REGRESSION day {} {}: {:.2?} -> {:.2?} ({:.2}x)",
+                regression.day,
+                regression.phase,
+                regression.baseline,
+                regression.current,
+                regression.slowdown()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn submit(
+    inputs: &InputArgs,
+    provider: &InputProvider,
+    options: &Options,
+    day: u32,
+    part: Part,
+    answer: Option<String>,
+    ledger_dir: PathBuf,
+) -> Result<()> {
+    let Some(session) = &inputs.session else {
+        bail!("set AOC_SESSION to submit answers");
+    };
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let runner = days::get(day).ok_or_else(|| anyhow!("day {day} not found"))?;
+            let run = runner.run_with(&load_input(provider, day)?, &[part], options, false)?;
+            run.answers[0].answer.clone()
+        }
+    };
+
+    let mut ledger = Ledger::load(&ledger_dir, day)?;
+    let submitter = HttpSubmitter::new(&inputs.base_url, session);
+    match submit::submit(&submitter, &mut ledger, day, part, &answer) {
+        Ok(verdict) => println!("{day}.{part}: {answer} is {verdict}"),
+        Err(e) => match e.downcast_ref::<Refusal>() {
+            Some(refusal) => println!("{day}.{part}: not sending {answer}: {refusal}"),
+            None => return Err(e),
+        },
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let provider = cli.inputs.provider();
+    match cli.command {
+        Command::Run {
+            target,
+            part,
+            input,
+            format,
+        } => {
+            let parts = part.map_or(Part::BOTH.to_vec(), |part| vec![part]);
+            let verbose = cli.verbose > 0;
+            match target {
+                Target::Day(day) => {
+                    let runner = days::get(day).ok_or_else(|| anyhow!("day {day} not found"))?;
+                    let input = match input {
+                        Some(path) => read_input(&path)?,
+                        None => load_input(&provider, day)?,
+                    };
+                    let options = cli.options.for_day(day)?;
+                    if format != Format::Text {
+                        let run = runner.run_with(&input, &parts, &options, verbose);
+                        if print_records(std::iter::once((day, run)), format, verbose)? {
+                            std::process::exit(1);
+                        }
+                        return Ok(());
+                    }
+                    let run = match runner.run_with(&input, &parts, &options, verbose) {
+                        Ok(run) => run,
+                        Err(e) => {
+                            eprintln!("{e:#}");
+                            std::process::exit(1);
+                        }
+                    };
+                    if verbose {
+                        print_diagnostics(&run);
+                    }
+                    for answer in &run.answers {
+                        println!("{day}.{}: {}", answer.part, answer.answer);
+                    }
+                }
+                Target::All => {
+                    if input.is_some() {
+                        bail!("--input can only be used with a single day");
+                    }
+                    let runs = days::DAYS.iter().map(|runner| {
+                        let day = runner.day();
+                        let run = load_input(&provider, day).and_then(|input| {
+                            runner.run_with(&input, &parts, &cli.options.for_day(day)?, verbose)
+                        });
+                        (day, run)
+                    });
+                    if format != Format::Text {
+                        if print_records(runs, format, verbose)? {
+                            std::process::exit(1);
+                        }
+                        return Ok(());
+                    }
+                    let runs = runs.collect::<Vec<_>>();
+                    if verbose {
+                        runs.iter()
+                            .filter_map(|(_, run)| run.as_ref().ok())
+                            .for_each(print_diagnostics);
+                    }
+                    print_table(&runs);
+                }
+            }
+        }
+        Command::Explain { day, input } => {
+            let Target::Day(day) = day else {
+                bail!("explain works on one day at a time");
+            };
+            let runner = days::get(day).ok_or_else(|| anyhow!("day {day} not found"))?;
+            let input = match input {
+                Some(path) => read_input(&path)?,
+                None => load_input(&provider, day)?,
+            };
+            match runner.explain(&input, &cli.options.for_day(day)?) {
+                Ok(Some(report)) => print!("{report}"),
+                Ok(None) => bail!("day {day} has no report"),
+                Err(e) => {
+                    eprintln!("{e:#}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Bench {
+            target,
+            iterations,
+            save,
+            baseline,
+            threshold,
+        } => bench(
+            &provider,
+            &cli.options,
+            target,
+            iterations,
+            save,
+            baseline,
+            threshold,
+        )?,
+        Command::Submit {
+            day,
+            part,
+            answer,
+            ledger_dir,
+        } => {
+            let Target::Day(day) = day else {
+                bail!("answers can only be submitted one day at a time");
+            };
+            let options = cli.options.for_day(day)?;
+            submit(
+                &cli.inputs,
+                &provider,
+                &options,
+                day,
+                part,
+                answer,
+                ledger_dir,
+            )?
+        }
+    }
+    Ok(())
+}
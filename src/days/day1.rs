@@ -0,0 +1,680 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+    io::BufRead,
+    ops::RangeInclusive,
+};
+
+use anyhow::{bail, Result};
+use itertools::Itertools;
+
+use crate::{
+    parse::{number, ParseError},
+    Options, Solution,
+};
+
+pub struct Day1;
+
+fn parse_line(line: &str) -> Result<(u32, u32), ParseError> {
+    let parts = line.split_whitespace().collect_vec();
+    if parts.len() != 2 {
+        let token = parts.get(2).copied().unwrap_or(&line[line.len()..]);
+        return Err(ParseError::at(line, token, "expected two numbers"));
+    }
+    Ok((number(line, parts[0])?, number(line, parts[1])?))
+}
+
+// calls `f` with each pair of numbers in `reader`, a line at a time, so
+// only one line is ever held in memory
+fn read_pairs(mut reader: impl BufRead, mut f: impl FnMut(u32, u32)) -> Result<()> {
+    let mut line = String::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        line_number += 1;
+        let (a, b) =
+            parse_line(line.trim_end_matches(['\n', '\r'])).map_err(|e| e.on_line(line_number))?;
+        f(a, b);
+    }
+}
+
+fn sorted((mut array1, mut array2): (Vec<u32>, Vec<u32>)) -> (Vec<u32>, Vec<u32>) {
+    array1.sort();
+    array2.sort();
+    (array1, array2)
+}
+
+fn parse(input: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+    let pairs = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_line(line).map_err(|e| e.on_line(i + 1)))
+        .collect::<Result<Vec<(u32, u32)>, _>>()?;
+    Ok(sorted(pairs.into_iter().unzip()))
+}
+
+/// Reads both lists from a stream, without loading the text of the whole
+/// input at once.
+pub fn parse_reader(reader: impl BufRead) -> Result<(Vec<u32>, Vec<u32>)> {
+    let (mut array1, mut array2) = (Vec::new(), Vec::new());
+    read_pairs(reader, |a, b| {
+        array1.push(a);
+        array2.push(b);
+    })?;
+    Ok(sorted((array1, array2)))
+}
+
+// both lists come sorted from `parse`
+fn part1(array1: &[u32], array2: &[u32]) -> u64 {
+    array1
+        .iter()
+        .zip(array2)
+        .map(|(a, b)| a.abs_diff(*b) as u64)
+        .sum()
+}
+
+fn part2(array1: &[u32], array2: &[u32]) -> u64 {
+    let counts = array2.iter().counts();
+    array1
+        .iter()
+        .map(|x| *x as u64 * counts.get(x).copied().unwrap_or(0) as u64)
+        .sum()
+}
+
+/// Part 2 straight from a stream, in memory proportional to the number of
+/// distinct values rather than the length of the lists.
+pub fn similarity(reader: impl BufRead) -> Result<u64> {
+    let (mut counts1, mut counts2) = (HashMap::new(), HashMap::new());
+    read_pairs(reader, |a, b| {
+        *counts1.entry(a).or_insert(0u64) += 1;
+        *counts2.entry(b).or_insert(0u64) += 1;
+    })?;
+    Ok(counts1
+        .iter()
+        .map(|(x, n)| *x as u64 * n * counts2.get(x).copied().unwrap_or(0))
+        .sum())
+}
+
+/// Reads lists given as columns: any number of them, as long as every line
+/// has the same number of values.
+pub fn parse_columns(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+    let width = input
+        .lines()
+        .next()
+        .map_or(0, |line| line.split_whitespace().count());
+    let mut columns = vec![Vec::new(); width];
+    for (i, line) in input.lines().enumerate() {
+        let values = line.split_whitespace().collect_vec();
+        if values.len() != width {
+            let token = values.get(width).copied().unwrap_or(&line[line.len()..]);
+            let message = format!("expected {width} numbers, found {}", values.len());
+            return Err(ParseError::at(line, token, message).on_line(i + 1));
+        }
+        for (column, value) in columns.iter_mut().zip(values) {
+            column.push(number(line, value).map_err(|e| e.on_line(i + 1))?);
+        }
+    }
+    Ok(columns)
+}
+
+/// How values in the other lists are matched up with values in the first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pairing {
+    /// Smallest with smallest, second smallest with second smallest, and so
+    /// on, as in the puzzle
+    SortedZip,
+    /// Whichever matching has the lowest total distance (by the Hungarian
+    /// algorithm, so cubic in the length of the lists)
+    MinCost,
+}
+
+/// How far apart two matched values are.
+pub enum Distance {
+    Absolute,
+    Squared,
+    Custom(Box<dyn Fn(u32, u32) -> u64>),
+}
+
+impl Distance {
+    pub fn custom(f: impl Fn(u32, u32) -> u64 + 'static) -> Distance {
+        Distance::Custom(Box::new(f))
+    }
+
+    pub fn between(&self, a: u32, b: u32) -> u64 {
+        match self {
+            Distance::Absolute => a.abs_diff(b) as u64,
+            Distance::Squared => (a.abs_diff(b) as u64).pow(2),
+            Distance::Custom(f) => f(a, b),
+        }
+    }
+}
+
+/// One value from each list. `distance` is the sum of the distances from
+/// the first list's value to each of the others.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub values: Vec<u32>,
+    pub distance: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconciliation {
+    /// In order of the first list's values
+    pub matches: Vec<Match>,
+    pub total: u64,
+}
+
+/// Lists given to [`reconcile`] that aren't all the same length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ragged {
+    /// Which list, counting from 0
+    pub column: usize,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Display for Ragged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let values = if self.found == 1 { "value" } else { "values" };
+        write!(
+            f,
+            "list {} has {} {values}, expected {}",
+            self.column, self.found, self.expected
+        )
+    }
+}
+
+impl std::error::Error for Ragged {}
+
+/// Matches every list against the first, which must all be the same length.
+/// Each other list is matched independently, so with [`Pairing::MinCost`]
+/// the total is the lowest possible.
+pub fn reconcile(
+    columns: &[Vec<u32>],
+    pairing: Pairing,
+    distance: &Distance,
+) -> Result<Reconciliation, Ragged> {
+    let Some(first) = columns.first() else {
+        return Ok(Reconciliation {
+            matches: Vec::new(),
+            total: 0,
+        });
+    };
+    if let Some((column, found)) = columns
+        .iter()
+        .map(Vec::len)
+        .enumerate()
+        .find(|&(_, len)| len != first.len())
+    {
+        return Err(Ragged {
+            column,
+            expected: first.len(),
+            found,
+        });
+    }
+    let first = first.iter().copied().sorted().collect_vec();
+    let mut matches = first
+        .iter()
+        .map(|&value| Match {
+            values: vec![value],
+            distance: 0,
+        })
+        .collect_vec();
+
+    for column in &columns[1..] {
+        let paired = match pairing {
+            Pairing::SortedZip => column.iter().copied().sorted().collect_vec(),
+            Pairing::MinCost => {
+                let assigned =
+                    min_cost_assignment(first.len(), |i, j| distance.between(first[i], column[j]));
+                assigned.iter().map(|&j| column[j]).collect_vec()
+            }
+        };
+        for (m, value) in matches.iter_mut().zip(paired) {
+            m.distance += distance.between(m.values[0], value);
+            m.values.push(value);
+        }
+    }
+
+    Ok(Reconciliation {
+        total: matches.iter().map(|m| m.distance).sum(),
+        matches,
+    })
+}
+
+// The Hungarian algorithm over an `n` by `n` cost matrix: for each row, the
+// column it is assigned to. Potentials can go negative, and costs can be up
+// to `u64::MAX`, hence `i128`.
+fn min_cost_assignment(n: usize, cost: impl Fn(usize, usize) -> u64) -> Vec<usize> {
+    // 1-based, with row/column 0 as a sentinel; `row[j]` is the row assigned
+    // to column `j`
+    let mut u = vec![0i128; n + 1];
+    let mut v = vec![0i128; n + 1];
+    let mut row = vec![0usize; n + 1];
+    let mut way = vec![0usize; n + 1];
+    for i in 1..=n {
+        row[0] = i;
+        let mut j0 = 0;
+        let mut min = vec![i128::MAX; n + 1];
+        let mut used = vec![false; n + 1];
+        while row[j0] != 0 {
+            used[j0] = true;
+            let i0 = row[j0];
+            let (mut delta, mut j1) = (i128::MAX, 0);
+            for j in (1..=n).filter(|&j| !used[j]) {
+                let reduced = cost(i0 - 1, j - 1) as i128 - u[i0] - v[j];
+                if reduced < min[j] {
+                    min[j] = reduced;
+                    way[j] = j0;
+                }
+                if min[j] < delta {
+                    delta = min[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=n {
+                if used[j] {
+                    u[row[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min[j] -= delta;
+                }
+            }
+            j0 = j1;
+        }
+        while j0 != 0 {
+            let j1 = way[j0];
+            row[j0] = row[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut assigned = vec![0; n];
+    for j in 1..=n {
+        assigned[row[j] - 1] = j - 1;
+    }
+    assigned
+}
+
+/// One sorted pair, with what it adds to each part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportRow {
+    pub left: u32,
+    pub right: u32,
+    pub distance: u64,
+    /// How many times `left` appears in the right list
+    pub multiplicity: u64,
+    /// `left * multiplicity`, this row's share of the similarity score
+    pub similarity: u64,
+}
+
+/// Every pair behind both answers, for working out why one is wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub rows: Vec<ReportRow>,
+    pub distance: u64,
+    pub similarity: u64,
+    /// Values in the left list that never appear in the right, and the other
+    /// way round
+    pub unmatched_left: Vec<u32>,
+    pub unmatched_right: Vec<u32>,
+}
+
+impl Report {
+    pub fn new(array1: &[u32], array2: &[u32]) -> Result<Report, Ragged> {
+        let pairs = reconcile(
+            &[array1.to_vec(), array2.to_vec()],
+            Pairing::SortedZip,
+            &Distance::Absolute,
+        )?;
+        let counts1 = array1.iter().counts();
+        let counts2 = array2.iter().counts();
+        let rows = pairs
+            .matches
+            .iter()
+            .map(|m| {
+                let (left, right) = (m.values[0], m.values[1]);
+                let multiplicity = counts2.get(&left).copied().unwrap_or(0) as u64;
+                ReportRow {
+                    left,
+                    right,
+                    distance: m.distance,
+                    multiplicity,
+                    similarity: left as u64 * multiplicity,
+                }
+            })
+            .collect_vec();
+        let unmatched = |counts: &HashMap<&u32, usize>, other: &HashMap<&u32, usize>| {
+            counts
+                .keys()
+                .filter(|value| !other.contains_key(*value))
+                .map(|&&value| value)
+                .sorted()
+                .collect_vec()
+        };
+
+        Ok(Report {
+            distance: pairs.total,
+            similarity: rows.iter().map(|row| row.similarity).sum(),
+            unmatched_left: unmatched(&counts1, &counts2),
+            unmatched_right: unmatched(&counts2, &counts1),
+            rows,
+        })
+    }
+
+    /// The `k` pairs furthest apart, furthest first
+    pub fn top(&self, k: usize) -> Vec<&ReportRow> {
+        self.rows
+            .iter()
+            .sorted_by_key(|row| std::cmp::Reverse(row.distance))
+            .take(k)
+            .collect()
+    }
+
+    /// How many pairs have distances in each power-of-two bucket: `0`, `1`,
+    /// `2..=3`, `4..=7` and so on, leaving out empty buckets.
+    pub fn histogram(&self) -> Vec<(RangeInclusive<u64>, usize)> {
+        let bucket = |distance: u64| match distance {
+            0 => 0..=0,
+            d => {
+                let low = 1 << d.ilog2();
+                low..=low * 2 - 1
+            }
+        };
+        self.rows
+            .iter()
+            .map(|row| bucket(row.distance))
+            .sorted_by_key(|range| *range.start())
+            .dedup_with_count()
+            .map(|(count, range)| (range, count))
+            .collect()
+    }
+
+    /// Every pair, then the summary: the `top` furthest pairs, the histogram
+    /// and the unmatched values.
+    pub fn table(&self, top: usize) -> String {
+        let mut out = String::new();
+        let _ = self.write_table(&mut out, top);
+        out
+    }
+
+    fn write_table(&self, out: &mut String, top: usize) -> std::fmt::Result {
+        let row = |out: &mut String, row: &ReportRow| {
+            writeln!(
+                out,
+                "{:>10} | {:>10} | {:>10} | {:>12} | {:>12}",
+                row.left, row.right, row.distance, row.multiplicity, row.similarity
+            )
+        };
+        let header = format!(
+            "{:>10} | {:>10} | {:>10} | {:>12} | {:>12}",
+            "left", "right", "distance", "multiplicity", "similarity"
+        );
+
+        writeln!(out, "{header}\n{}", "-".repeat(header.len()))?;
+        for r in &self.rows {
+            row(out, r)?;
+        }
+        writeln!(out, "{}", "-".repeat(header.len()))?;
+        writeln!(
+            out,
+            "{:>36} | {:>12} | {:>12}",
+            self.distance, "", self.similarity
+        )?;
+
+        writeln!(out, "\ntop {top} by distance:\n{header}")?;
+        for r in self.top(top) {
+            row(out, r)?;
+        }
+
+        writeln!(out, "\ndistances:")?;
+        let most = self.histogram().iter().map(|(_, count)| *count).max();
+        for (range, count) in self.histogram() {
+            let bar = "#".repeat(count * 40 / most.unwrap_or(1).max(1));
+            let range = format!("{}..={}", range.start(), range.end());
+            writeln!(out, "{range:>24} | {count:>6} {bar}")?;
+        }
+
+        writeln!(
+            out,
+            "\nonly in left:  {}",
+            self.unmatched_left.iter().join(" ")
+        )?;
+        writeln!(
+            out,
+            "only in right: {}",
+            self.unmatched_right.iter().join(" ")
+        )
+    }
+
+    /// One line per pair, with a header.
+    pub fn csv(&self) -> String {
+        let mut out = String::from("left,right,distance,multiplicity,similarity\n");
+        for row in &self.rows {
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{}",
+                row.left, row.right, row.distance, row.multiplicity, row.similarity
+            );
+        }
+        out
+    }
+}
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    type Input = (Vec<u32>, Vec<u32>);
+    type Output1 = u64;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1((array1, array2): &Self::Input) -> u64 {
+        part1(array1, array2)
+    }
+
+    fn part2((array1, array2): &Self::Input) -> u64 {
+        part2(array1, array2)
+    }
+
+    /// Options: `format` (`table` or `csv`) and `top`, how many of the
+    /// furthest pairs to list.
+    fn explain((array1, array2): &Self::Input, options: &Options) -> Result<Option<String>> {
+        let report = Report::new(array1, array2)?;
+        match options.get_or("format", "table".to_owned())?.as_str() {
+            "table" => Ok(Some(report.table(options.get_or("top", 5)?))),
+            "csv" => Ok(Some(report.csv())),
+            format => bail!("unknown format `{format}`, expected `table` or `csv`"),
+        }
+    }
+}
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "3   4
+        4   3
+        2   5
+        1   3
+        3   9
+        3   3";
+
+    use super::*;
+    use std::io::BufReader;
+
+    use crate::generate::{self, Rng};
+
+    #[test]
+    fn test_part1() {
+        let (p1, p2) = parse(INPUT).unwrap();
+        assert_eq!(part1(&p1, &p2), 11);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse("3   4\n4   x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+
+        let error = parse("3   4\n4\n").unwrap_err();
+        assert_eq!(error.message, "expected two numbers");
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
+    #[test]
+    fn test_part2() {
+        let (p1, p2) = parse(INPUT).unwrap();
+        assert_eq!(part2(&p1, &p2), 31);
+    }
+
+    #[test]
+    fn test_part1_oracle() {
+        for seed in 0..200 {
+            let input = generate::day1(&mut Rng::new(seed));
+            let (p1, p2) = parse(&input).unwrap();
+            // the cheapest way to pair the lists up, out of every way
+            let oracle = p2
+                .iter()
+                .permutations(p2.len())
+                .map(|p2| {
+                    p1.iter()
+                        .zip(p2)
+                        .map(|(a, b)| a.abs_diff(*b) as u64)
+                        .sum::<u64>()
+                })
+                .min()
+                .unwrap();
+            assert_eq!(part1(&p1, &p2), oracle, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        for seed in 0..50 {
+            let input = generate::day1(&mut Rng::new(seed));
+            // a tiny buffer, so lines are split between reads
+            let reader = BufReader::with_capacity(3, input.as_bytes());
+            assert_eq!(parse_reader(reader).unwrap(), parse(&input).unwrap());
+
+            let (p1, p2) = parse(&input).unwrap();
+            assert_eq!(similarity(input.as_bytes()).unwrap(), part2(&p1, &p2));
+        }
+
+        let error = parse_reader("3   4\n4   x\n".as_bytes()).unwrap_err();
+        let error = error.downcast::<ParseError>().unwrap();
+        assert_eq!((error.line, error.column), (2, 5));
+    }
+
+    #[test]
+    fn test_part2_large() {
+        // quadratic in the length of the lists, this would take minutes
+        let input = (0..200_000u32)
+            .map(|i| format!("{}   {}\n", i % 1000, i % 777))
+            .collect::<String>();
+        let (p1, p2) = parse(&input).unwrap();
+        assert_eq!(part2(&p1, &p2), similarity(input.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let columns = parse_columns(INPUT).unwrap();
+        let zipped = reconcile(&columns, Pairing::SortedZip, &Distance::Absolute).unwrap();
+        assert_eq!(zipped.total, 11);
+        assert_eq!(zipped.matches[0].values, [1, 3]);
+        assert_eq!(zipped.matches[5].values, [4, 9]);
+        assert_eq!(zipped.matches[5].distance, 5);
+
+        let squared = reconcile(&columns, Pairing::SortedZip, &Distance::Squared).unwrap();
+        assert_eq!(squared.total, 35);
+
+        // only ever rewards matching a value with itself
+        let equal = Distance::custom(|a, b| (a != b) as u64);
+        let assigned = reconcile(&columns, Pairing::MinCost, &equal).unwrap();
+        assert_eq!(assigned.total, 2);
+    }
+
+    #[test]
+    fn test_reconcile_columns() {
+        let columns = parse_columns("1 2 10\n5 6 1\n").unwrap();
+        let result = reconcile(&columns, Pairing::MinCost, &Distance::Absolute).unwrap();
+        assert_eq!(result.matches[0].values, [1, 2, 1]);
+        assert_eq!(result.matches[1].values, [5, 6, 10]);
+        assert_eq!(result.total, 1 + 1 + 5);
+
+        let ragged = [vec![1, 2], vec![3, 4], vec![5]];
+        let error = reconcile(&ragged, Pairing::SortedZip, &Distance::Absolute).unwrap_err();
+        assert_eq!(error.to_string(), "list 2 has 1 value, expected 2");
+        let ragged = [vec![1], vec![2, 3, 4]];
+        let error = reconcile(&ragged, Pairing::SortedZip, &Distance::Absolute).unwrap_err();
+        assert_eq!(error.to_string(), "list 1 has 3 values, expected 1");
+        assert!(Report::new(&[1, 2], &[3]).is_err());
+
+        let error = parse_columns("1 2 3\n4 5\n").unwrap_err();
+        assert_eq!(error.message, "expected 3 numbers, found 2");
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
+    #[test]
+    fn test_min_cost_oracle() {
+        for seed in 0..100 {
+            let columns = parse_columns(&generate::day1(&mut Rng::new(seed))).unwrap();
+            let distance = Distance::custom(|a, b| (a * 7 + b * 3) as u64 % 11);
+            let result = reconcile(&columns, Pairing::MinCost, &distance).unwrap();
+            let oracle = columns[1]
+                .iter()
+                .permutations(columns[1].len())
+                .map(|other| {
+                    columns[0]
+                        .iter()
+                        .zip(other)
+                        .map(|(&a, &b)| distance.between(a, b))
+                        .sum::<u64>()
+                })
+                .min()
+                .unwrap();
+            assert_eq!(result.total, oracle, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let (p1, p2) = parse(INPUT).unwrap();
+        let report = Report::new(&p1, &p2).unwrap();
+        assert_eq!((report.distance, report.similarity), (11, 31));
+        assert_eq!(report.rows.len(), 6);
+        assert_eq!(report.rows[2].left, 3);
+        assert_eq!(
+            (report.rows[2].multiplicity, report.rows[2].similarity),
+            (3, 9)
+        );
+
+        assert_eq!(report.top(1)[0].distance, 5);
+        assert_eq!(
+            report.histogram(),
+            [(0..=0, 1), (1..=1, 2), (2..=3, 2), (4..=7, 1)]
+        );
+        assert_eq!(report.unmatched_left, [1, 2]);
+        assert_eq!(report.unmatched_right, [5, 9]);
+
+        let csv = report.csv();
+        assert_eq!(csv.lines().count(), 7);
+        assert_eq!(csv.lines().nth(1), Some("1,3,2,0,0"));
+        assert!(report.table(3).contains("only in right: 5 9"));
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = parse(INPUT).unwrap();
+        let csv = Options::new().with("format", "csv");
+        assert!(Day1::explain(&input, &csv)
+            .unwrap()
+            .unwrap()
+            .starts_with("left,right"));
+
+        let bad = Options::new().with("format", "xml");
+        assert!(Day1::explain(&input, &bad).is_err());
+    }
+}
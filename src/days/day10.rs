@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+
+use crate::{
+    grid::{Grid, Point},
+    ParseError, Solution,
+};
+
+pub struct Day10;
+
+fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse(input, |c| c.to_digit(10).map(|d| d as u8))
+}
+
+fn _ascending_paths(grid: &Grid<u8>, point: Point, out: &mut HashSet<Point>) {
+    let val = grid[point];
+    if val == 9 {
+        out.insert(point);
+        return;
+    }
+    for nbr in grid.neighbours4(point).filter(|&nbr| grid[nbr] == val + 1) {
+        _ascending_paths(grid, nbr, out);
+    }
+}
+
+fn ascending_paths(grid: &Grid<u8>, point: Point) -> usize {
+    let mut map = HashSet::new();
+    _ascending_paths(grid, point, &mut map);
+    map.len()
+}
+
+fn part1(grid: &Grid<u8>) -> usize {
+    grid.enumerate()
+        .filter(|(_, &val)| val == 0)
+        .map(|(point, _)| ascending_paths(grid, point))
+        .sum()
+}
+
+fn trailhead_ratings(grid: &Grid<u8>, point: Point) -> usize {
+    let val = grid[point];
+    if val == 9 {
+        return 1;
+    }
+    grid.neighbours4(point)
+        .filter(|&nbr| grid[nbr] == val + 1)
+        .map(|nbr| trailhead_ratings(grid, nbr))
+        .sum()
+}
+
+fn part2(grid: &Grid<u8>) -> usize {
+    grid.enumerate()
+        .filter(|(_, &val)| val == 0)
+        .map(|(point, _)| trailhead_ratings(grid, point))
+        .sum()
+}
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    type Input = Grid<u8>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        part2(input)
+    }
+}
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
+    use super::*;
+    use crate::generate::{self, Rng};
+
+    #[test]
+    fn test_part1() {
+        let grid = parse(INPUT).unwrap();
+        assert_eq!(part1(&grid), 36);
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = parse(INPUT).unwrap();
+        assert_eq!(part2(&input), 81);
+    }
+
+    #[test]
+    fn test_oracle() {
+        for seed in 0..200 {
+            let grid = parse(&generate::day10(&mut Rng::new(seed))).unwrap();
+            // peaks reachable from each cell, and the number of trails to them,
+            // working down from the peaks one height at a time
+            let mut peaks = grid.map(|_| HashSet::new());
+            let mut trails = grid.map(|_| 0);
+            for height in (0..=9).rev() {
+                for point in grid.points().filter(|&p| grid[p] == height) {
+                    if height == 9 {
+                        peaks[point].insert(point);
+                        trails[point] = 1;
+                    }
+                    for next in grid.neighbours4(point).filter(|&p| grid[p] == height + 1) {
+                        let above = peaks[next].clone();
+                        peaks[point].extend(above);
+                        trails[point] += trails[next];
+                    }
+                }
+            }
+            let trailheads = grid.points().filter(|&p| grid[p] == 0).collect::<Vec<_>>();
+            let score: usize = trailheads.iter().map(|&p| peaks[p].len()).sum();
+            let rating: usize = trailheads.iter().map(|&p| trails[p]).sum();
+
+            assert_eq!(part1(&grid), score, "seed {seed}");
+            assert_eq!(part2(&grid), rating, "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,608 @@
+use std::{
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use anyhow::{bail, Result};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    ansi::{self, Style},
+    parse::{number, ParseError},
+    Options, Solution,
+};
+
+pub struct Day2;
+
+// a blank line would be an empty report, which the default policy counts
+// as safe, so one left in by accident would quietly change the answer
+fn parse_rows(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                return Err(ParseError::at(input, line, "unexpected blank line"));
+            }
+            line.split_whitespace().map(|x| number(input, x)).collect()
+        })
+        .collect()
+}
+
+// the fewest levels (at most `k`) to remove so that every step between the
+// levels that are left is accepted by `step`
+fn dampen_with(row: &[u32], k: usize, step: impl Fn(u32, u32) -> bool) -> Option<Vec<usize>> {
+    let n = row.len();
+    if n == 0 {
+        return Some(Vec::new());
+    }
+    // `removed[i]` is the fewest levels removed before `i` if `i` is kept,
+    // and `previous[i]` is the level kept before it. Only the last `k + 1`
+    // levels can come before `i`, so this is O(nk).
+    let mut removed = (0..n).map(|i| (i <= k).then_some(i)).collect_vec();
+    let mut previous = vec![None; n];
+    for i in 1..n {
+        for j in i.saturating_sub(k + 1)..i {
+            let Some(before) = removed[j] else { continue };
+            let total = before + (i - j - 1);
+            if total <= k && step(row[j], row[i]) && removed[i].is_none_or(|r| total < r) {
+                removed[i] = Some(total);
+                previous[i] = Some(j);
+            }
+        }
+    }
+
+    let last = (n.saturating_sub(k + 1)..n)
+        .filter_map(|i| Some((i, removed[i]? + (n - 1 - i))))
+        .filter(|&(_, total)| total <= k)
+        .min_by_key(|&(_, total)| total)?
+        .0;
+    let mut kept = vec![false; n];
+    let mut level = Some(last);
+    while let Some(i) = level {
+        kept[i] = true;
+        level = previous[i];
+    }
+    Some((0..n).filter(|&i| !kept[i]).collect())
+}
+
+/// Which way a report's levels may go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Increasing,
+    Decreasing,
+    /// Either way, as long as the whole report goes the same way
+    Either,
+}
+
+impl FromStr for Trend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Trend, String> {
+        match s {
+            "increasing" => Ok(Trend::Increasing),
+            "decreasing" => Ok(Trend::Decreasing),
+            "either" => Ok(Trend::Either),
+            _ => Err("expected `increasing`, `decreasing` or `either`".to_owned()),
+        }
+    }
+}
+
+/// What makes a report safe. The default is the puzzle's rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    /// The smallest and largest allowed change between adjacent levels
+    pub min_step: u32,
+    pub max_step: u32,
+    pub trend: Trend,
+    /// Whether adjacent levels may be equal, whatever `min_step` says
+    pub plateaus: bool,
+    /// Whether reports with fewer than two levels, which have no steps to
+    /// check, are safe. This also applies to what's left after dampening.
+    pub short_reports_safe: bool,
+    /// How many levels the dampener may remove in part 2
+    pub tolerance: usize,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> SafetyPolicy {
+        SafetyPolicy {
+            min_step: 1,
+            max_step: 3,
+            trend: Trend::Either,
+            plateaus: false,
+            short_reports_safe: true,
+            tolerance: 1,
+        }
+    }
+}
+
+impl SafetyPolicy {
+    /// Reads `min_step`, `max_step`, `trend`, `plateaus`, `short_reports_safe`
+    /// and `tolerance`, keeping the default for any that aren't set.
+    pub fn from_options(options: &Options) -> Result<SafetyPolicy> {
+        let default = SafetyPolicy::default();
+        let policy = SafetyPolicy {
+            min_step: options.get_or("min_step", default.min_step)?,
+            max_step: options.get_or("max_step", default.max_step)?,
+            trend: options.get_or("trend", default.trend)?,
+            plateaus: options.get_or("plateaus", default.plateaus)?,
+            short_reports_safe: options.get_or("short_reports_safe", default.short_reports_safe)?,
+            tolerance: options.get_or("tolerance", default.tolerance)?,
+        };
+        if policy.min_step > policy.max_step {
+            bail!(
+                "min_step ({}) is larger than max_step ({})",
+                policy.min_step,
+                policy.max_step
+            );
+        }
+        Ok(policy)
+    }
+
+    fn step(&self, a: u32, b: u32, increasing: bool) -> bool {
+        if a == b {
+            return self.plateaus;
+        }
+        (a < b) == increasing && (self.min_step..=self.max_step).contains(&a.abs_diff(b))
+    }
+
+    /// The fewest levels, at most `k`, that can be removed from `row` to make
+    /// it safe, as indices into `row`, or `None` if it takes more than `k`.
+    /// Linear in the length of the row for a fixed `k`.
+    pub fn dampen(&self, row: &[u32], k: usize) -> Option<Vec<usize>> {
+        let increasing = matches!(self.trend, Trend::Increasing | Trend::Either)
+            .then(|| dampen_with(row, k, |a, b| self.step(a, b, true)));
+        let decreasing = matches!(self.trend, Trend::Decreasing | Trend::Either)
+            .then(|| dampen_with(row, k, |a, b| self.step(a, b, false)));
+        // removing as few levels as possible keeps as many as possible, so
+        // if this leaves a short report, every way of removing levels does
+        [increasing, decreasing]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .min_by_key(Vec::len)
+            .filter(|removed| self.short_reports_safe || row.len() - removed.len() >= 2)
+    }
+
+    pub fn is_safe(&self, row: &[u32]) -> bool {
+        self.dampen(row, 0).is_some()
+    }
+
+    /// How many reports are safe after removing up to `k` levels from each.
+    pub fn count_safe(&self, rows: &[Vec<u32>], k: usize) -> u32 {
+        rows.iter()
+            .filter(|row| self.dampen(row, k).is_some())
+            .count() as u32
+    }
+}
+
+/// What's wrong with a step between two levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    StepTooLarge,
+    StepTooSmall,
+    ZeroStep,
+    /// Against the way the report has been going so far
+    DirectionChange,
+    /// Against the policy's [`Trend`]
+    WrongDirection,
+    /// Fewer than two levels, when those aren't safe
+    TooShort,
+}
+
+impl Display for ViolationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            ViolationKind::StepTooLarge => "step too large",
+            ViolationKind::StepTooSmall => "step too small",
+            ViolationKind::ZeroStep => "zero step",
+            ViolationKind::DirectionChange => "direction change",
+            ViolationKind::WrongDirection => "wrong direction",
+            ViolationKind::TooShort => "too short",
+        };
+        write!(f, "{kind}")
+    }
+}
+
+/// The first bad step in a report: from level `index - 1` to level `index`.
+/// For a report that is too short, `index` is 0 and `levels` is empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub index: usize,
+    pub levels: Vec<u32>,
+    pub kind: ViolationKind,
+}
+
+/// Why a report is (or isn't) safe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Verdict {
+    pub violation: Option<Violation>,
+    /// For unsafe reports, the levels the dampener removed to make it safe,
+    /// if it could
+    pub dropped: Option<Vec<usize>>,
+}
+
+impl Verdict {
+    pub fn is_safe(&self) -> bool {
+        self.violation.is_none() || self.dropped.is_some()
+    }
+}
+
+impl SafetyPolicy {
+    /// The first step in `row` that breaks the policy. A report has one
+    /// exactly when it isn't safe.
+    pub fn first_violation(&self, row: &[u32]) -> Option<Violation> {
+        if row.len() < 2 {
+            return (!self.short_reports_safe).then(|| Violation {
+                index: 0,
+                levels: Vec::new(),
+                kind: ViolationKind::TooShort,
+            });
+        }
+        // with `Trend::Either`, the first step that goes anywhere decides
+        let mut increasing = match self.trend {
+            Trend::Increasing => Some(true),
+            Trend::Decreasing => Some(false),
+            Trend::Either => None,
+        };
+        row.iter()
+            .tuple_windows()
+            .zip(1..)
+            .find_map(|((&a, &b), index)| {
+                let violation = |kind| {
+                    Some(Violation {
+                        index,
+                        levels: vec![a, b],
+                        kind,
+                    })
+                };
+                if a == b {
+                    return if self.plateaus {
+                        None
+                    } else {
+                        violation(ViolationKind::ZeroStep)
+                    };
+                }
+                match increasing {
+                    Some(increasing) if increasing != (a < b) => {
+                        return violation(match self.trend {
+                            Trend::Either => ViolationKind::DirectionChange,
+                            _ => ViolationKind::WrongDirection,
+                        });
+                    }
+                    None => increasing = Some(a < b),
+                    _ => {}
+                }
+                let step = a.abs_diff(b);
+                if step > self.max_step {
+                    violation(ViolationKind::StepTooLarge)
+                } else if step < self.min_step {
+                    violation(ViolationKind::StepTooSmall)
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Why `row` is or isn't safe, dampening with the policy's tolerance.
+    pub fn verdict(&self, row: &[u32]) -> Verdict {
+        let violation = self.first_violation(row);
+        let dropped = match violation {
+            Some(_) => self.dampen(row, self.tolerance),
+            None => None,
+        };
+        Verdict { violation, dropped }
+    }
+}
+
+/// Every report on its own line, with the offending levels and the levels
+/// the dampener dropped picked out, and what was wrong with it. Without
+/// colour, offending levels are shown `[like this]` and dropped ones
+/// `(like this)`.
+pub fn annotate(rows: &[Vec<u32>], verdicts: &[Verdict], color: bool) -> String {
+    let mut out = String::new();
+    let width = rows.len().to_string().len();
+    for (line, (row, verdict)) in rows.iter().zip(verdicts).enumerate() {
+        let offending = match &verdict.violation {
+            Some(v) if !v.levels.is_empty() => vec![v.index - 1, v.index],
+            _ => Vec::new(),
+        };
+        let dropped = verdict.dropped.as_deref().unwrap_or_default();
+        let levels = row
+            .iter()
+            .enumerate()
+            .map(
+                |(i, level)| match (dropped.contains(&i), offending.contains(&i), color) {
+                    (true, _, true) => Style::STRIKE.paint(Style::DIM.paint(level)),
+                    (true, _, false) => format!("({level})"),
+                    (false, true, true) => Style::RED.paint(level),
+                    (false, true, false) => format!("[{level}]"),
+                    (false, false, _) => level.to_string(),
+                },
+            )
+            .join(" ");
+
+        let summary = match (&verdict.violation, &verdict.dropped) {
+            (None, _) => "safe".to_owned(),
+            (Some(v), dropped) => {
+                let what = match v.kind {
+                    ViolationKind::TooShort => format!("unsafe: {}", v.kind),
+                    _ => format!(
+                        "unsafe: {} ({} -> {} at index {})",
+                        v.kind, v.levels[0], v.levels[1], v.index
+                    ),
+                };
+                match dropped {
+                    Some(dropped) => format!(
+                        "{what}, rescued by dropping index {}",
+                        dropped.iter().join(", ")
+                    ),
+                    None => format!("{what}, not rescued"),
+                }
+            }
+        };
+        let summary = match (color, verdict.violation.is_none(), verdict.is_safe()) {
+            (false, _, _) => summary,
+            (true, true, _) => Style::GREEN.paint(summary),
+            (true, false, true) => Style::YELLOW.paint(summary),
+            (true, false, false) => Style::RED.paint(summary),
+        };
+        let _ = writeln!(out, "{:>width$} | {levels} | {summary}", line + 1);
+    }
+    out
+}
+
+fn parse(input: &str) -> Result<(SafetyPolicy, Vec<Vec<u32>>), ParseError> {
+    Ok((SafetyPolicy::default(), parse_rows(input)?))
+}
+
+/** Safe inputs */
+fn part1((policy, rows): &(SafetyPolicy, Vec<Vec<u32>>)) -> u32 {
+    policy.count_safe(rows, 0)
+}
+
+fn part2((policy, rows): &(SafetyPolicy, Vec<Vec<u32>>)) -> u32 {
+    policy.count_safe(rows, policy.tolerance)
+}
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+
+    type Input = (SafetyPolicy, Vec<Vec<u32>>);
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    /// Options: the fields of [`SafetyPolicy`].
+    fn parse_with(input: &str, options: &Options) -> Result<Self::Input> {
+        Ok((SafetyPolicy::from_options(options)?, parse_rows(input)?))
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+
+    /// Options: `format` (`text`, the default, or `json`) and `color`.
+    fn explain((policy, rows): &Self::Input, options: &Options) -> Result<Option<String>> {
+        let verdicts = rows.iter().map(|row| policy.verdict(row)).collect_vec();
+        match options.get_or("format", "text".to_owned())?.as_str() {
+            "text" => Ok(Some(annotate(rows, &verdicts, ansi::enabled(options)?))),
+            "json" => Ok(Some(serde_json::to_string_pretty(&verdicts)? + "\n")),
+            format => bail!("unknown format `{format}`, expected `text` or `json`"),
+        }
+    }
+}
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+
+    use super::*;
+    use crate::generate::{self, Rng};
+
+    fn row_is_safe(row: &[u32]) -> bool {
+        let ordering = row[0].cmp(&row[1]);
+        row.iter().tuple_windows().all(|(&x, &y)| {
+            let diff = x.abs_diff(y);
+            (1..=3).contains(&diff) && x.cmp(&y) == ordering
+        })
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse(INPUT).unwrap()), 2);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse(INPUT).unwrap()), 4);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse("1 2 3\n\n4 5 6").unwrap_err();
+        assert_eq!(error.message, "unexpected blank line");
+        assert_eq!(error.line, 2);
+        assert!(parse("1 2 3\n  \n4 5 6").is_err());
+        assert_eq!(part1(&parse("1 2 3\n4 5 6\n").unwrap()), 2);
+
+        let error = parse("1 2 x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+    }
+
+    #[test]
+    fn test_dampen() {
+        let policy = SafetyPolicy::default();
+        assert_eq!(policy.dampen(&[1, 5, 2, 3, 4], 1), Some(vec![1]));
+        assert_eq!(policy.dampen(&[8, 6, 4, 9, 1], 1), Some(vec![3]));
+        assert_eq!(policy.dampen(&[1, 2, 7, 8, 9], 1), None);
+        assert_eq!(policy.dampen(&[1, 2, 7, 8, 9], 2), Some(vec![0, 1]));
+        assert_eq!(policy.dampen(&[9, 1, 2, 3, 20], 2), Some(vec![0, 4]));
+        assert_eq!(policy.dampen(&[7, 6, 4, 2, 1], 0), Some(vec![]));
+    }
+
+    #[test]
+    fn test_policy() {
+        let default = SafetyPolicy::default();
+        assert!(default.is_safe(&[]) && default.is_safe(&[5]));
+        assert!(!default.is_safe(&[1, 1, 2]));
+        assert_eq!(
+            default.dampen(&[1, 9], 1).map(|removed| removed.len()),
+            Some(1)
+        );
+
+        let strict = SafetyPolicy {
+            short_reports_safe: false,
+            ..SafetyPolicy::default()
+        };
+        assert!(!strict.is_safe(&[]) && !strict.is_safe(&[5]));
+        assert_eq!(strict.dampen(&[1, 9], 1), None);
+
+        let options = Options::new()
+            .with("trend", "increasing")
+            .with("plateaus", "true")
+            .with("max_step", "5");
+        let policy = SafetyPolicy::from_options(&options).unwrap();
+        assert!(policy.is_safe(&[1, 1, 6, 6, 7]));
+        assert!(!policy.is_safe(&[7, 6, 6, 1]));
+        assert!(!policy.is_safe(&[1, 7]));
+
+        let input = Day2::parse_with(INPUT, &options.with("tolerance", "0")).unwrap();
+        assert_eq!(Day2::part2(&input), Day2::part1(&input));
+
+        let backwards = Options::new().with("min_step", "4");
+        assert!(SafetyPolicy::from_options(&backwards).is_err());
+        let unknown = Options::new().with("trend", "sideways");
+        assert!(SafetyPolicy::from_options(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_dampen_oracle() {
+        for seed in 0..300 {
+            for row in parse_rows(&generate::day2(&mut Rng::new(seed))).unwrap() {
+                for k in 0..=2 {
+                    // the fewest removals that leave a safe row, trying
+                    // every set of indices
+                    let oracle = (0..=k).find(|&count| {
+                        (0..row.len()).combinations(count).any(|removed| {
+                            let kept = (0..row.len())
+                                .filter(|i| !removed.contains(i))
+                                .map(|i| row[i])
+                                .collect_vec();
+                            kept.len() < 2 || row_is_safe(&kept)
+                        })
+                    });
+                    let removed = SafetyPolicy::default().dampen(&row, k);
+                    assert_eq!(
+                        removed.as_ref().map(Vec::len),
+                        oracle,
+                        "seed {seed}: {row:?}"
+                    );
+
+                    if let Some(removed) = removed {
+                        let kept = (0..row.len())
+                            .filter(|i| !removed.contains(i))
+                            .map(|i| row[i])
+                            .collect_vec();
+                        assert!(kept.len() < 2 || row_is_safe(&kept), "seed {seed}: {row:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_first_violation() {
+        let policy = SafetyPolicy::default();
+        let kind = |row: &[u32]| policy.first_violation(row).map(|v| (v.index, v.kind));
+        assert_eq!(kind(&[7, 6, 4, 2, 1]), None);
+        assert_eq!(
+            kind(&[1, 2, 7, 8, 9]),
+            Some((2, ViolationKind::StepTooLarge))
+        );
+        assert_eq!(
+            kind(&[1, 3, 2, 4, 5]),
+            Some((2, ViolationKind::DirectionChange))
+        );
+        assert_eq!(kind(&[8, 6, 4, 4, 1]), Some((3, ViolationKind::ZeroStep)));
+
+        let increasing = SafetyPolicy {
+            trend: Trend::Increasing,
+            min_step: 2,
+            ..SafetyPolicy::default()
+        };
+        let kind = |row: &[u32]| increasing.first_violation(row).map(|v| (v.index, v.kind));
+        assert_eq!(kind(&[3, 1]), Some((1, ViolationKind::WrongDirection)));
+        assert_eq!(kind(&[1, 3, 4]), Some((2, ViolationKind::StepTooSmall)));
+    }
+
+    #[test]
+    fn test_first_violation_agrees() {
+        let policies = [
+            SafetyPolicy::default(),
+            SafetyPolicy {
+                plateaus: true,
+                trend: Trend::Decreasing,
+                ..SafetyPolicy::default()
+            },
+            SafetyPolicy {
+                min_step: 2,
+                max_step: 5,
+                short_reports_safe: false,
+                ..SafetyPolicy::default()
+            },
+        ];
+        for seed in 0..200 {
+            for row in parse_rows(&generate::day2(&mut Rng::new(seed))).unwrap() {
+                for policy in &policies {
+                    let violation = policy.first_violation(&row);
+                    assert_eq!(violation.is_none(), policy.is_safe(&row), "{row:?}");
+                    if let Some(violation) = violation.filter(|v| !v.levels.is_empty()) {
+                        assert_eq!(violation.levels, row[violation.index - 1..=violation.index]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = parse(INPUT).unwrap();
+        let options = Options::new().with("color", "never");
+        let report = Day2::explain(&input, &options).unwrap().unwrap();
+        let lines = report.lines().collect_vec();
+        assert_eq!(lines[0], "1 | 7 6 4 2 1 | safe");
+        assert_eq!(
+            lines[1],
+            "2 | 1 [2] [7] 8 9 | unsafe: step too large (2 -> 7 at index 2), not rescued"
+        );
+        assert_eq!(
+            lines[3],
+            "4 | 1 [3] (2) 4 5 | unsafe: direction change (3 -> 2 at index 2), rescued by dropping index 2"
+        );
+
+        let colored = Day2::explain(&input, &Options::new().with("color", "always"));
+        assert_eq!(
+            ansi::strip(&colored.unwrap().unwrap()).lines().next(),
+            Some(lines[0])
+        );
+
+        let json = Day2::explain(&input, &Options::new().with("format", "json"));
+        let verdicts: serde_json::Value = serde_json::from_str(&json.unwrap().unwrap()).unwrap();
+        assert_eq!(verdicts[4]["violation"]["kind"], "zero_step");
+        assert_eq!(verdicts[4]["dropped"].as_array().map(Vec::len), Some(1));
+    }
+}
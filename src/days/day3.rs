@@ -0,0 +1,924 @@
+use std::{
+    fmt::Display,
+    io::{self, Read},
+};
+
+use anyhow::{bail, Result};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{Options, ParseError, Solution};
+
+pub struct Day3;
+
+/// One instruction found in corrupted memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+/// How an instruction is written: `name(a,b,...)`, with exactly `operands`
+/// operands of one to three digits each, and no spaces anywhere.
+struct Syntax {
+    name: &'static str,
+    operands: usize,
+    build: fn(&[u32]) -> Instruction,
+}
+
+/// Every instruction the lexer knows. Adding one is a new entry here and a
+/// new arm in [`Vm::step`].
+const SYNTAX: &[Syntax] = &[
+    Syntax {
+        name: "mul",
+        operands: 2,
+        build: |operands| Instruction::Mul(operands[0], operands[1]),
+    },
+    Syntax {
+        name: "do",
+        operands: 0,
+        build: |_| Instruction::Do,
+    },
+    Syntax {
+        name: "don't",
+        operands: 0,
+        build: |_| Instruction::Dont,
+    },
+];
+
+// why `syntax` wasn't read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Miss {
+    NoMatch,
+    /// `memory` ran out part way through, so more might still match
+    Incomplete,
+}
+
+// strips `token` from the start of `rest`
+fn expect(rest: &mut &[u8], token: &[u8]) -> Result<(), Miss> {
+    if let Some(after) = rest.strip_prefix(token) {
+        *rest = after;
+        Ok(())
+    } else if token.starts_with(rest) {
+        Err(Miss::Incomplete)
+    } else {
+        Err(Miss::NoMatch)
+    }
+}
+
+// reads `syntax` from the start of `memory`, returning the instruction and
+// how many bytes it took up
+fn read(memory: &[u8], syntax: &Syntax) -> Result<(Instruction, usize), Miss> {
+    let mut rest = memory;
+    expect(&mut rest, syntax.name.as_bytes())?;
+    expect(&mut rest, b"(")?;
+    let mut operands = Vec::with_capacity(syntax.operands);
+    for i in 0..syntax.operands {
+        if i > 0 {
+            expect(&mut rest, b",")?;
+        }
+        let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+        if digits == rest.len() && digits <= 3 {
+            // the next byte could still be a digit or the separator
+            return Err(Miss::Incomplete);
+        }
+        if !(1..=3).contains(&digits) {
+            return Err(Miss::NoMatch);
+        }
+        let operand = rest[..digits]
+            .iter()
+            .fold(0, |n, &digit| n * 10 + (digit - b'0') as u32);
+        operands.push(operand);
+        rest = &rest[digits..];
+    }
+    expect(&mut rest, b")")?;
+    Ok(((syntax.build)(&operands), memory.len() - rest.len()))
+}
+
+/// Every well-formed instruction read from `reader`, with its byte offset,
+/// skipping everything else. Instructions never overlap: scanning carries
+/// on after the end of each one found.
+///
+/// Only a chunk of input is held at a time, along with the few bytes of any
+/// instruction split across the end of it, so `reader` can be any size.
+pub struct Scanner<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    /// Where in `buffer` to look for the next instruction
+    start: usize,
+    /// The offset of `buffer[0]` in the whole input
+    offset: usize,
+    eof: bool,
+}
+
+impl<R: Read> Scanner<R> {
+    const CHUNK: usize = 8192;
+
+    pub fn new(reader: R) -> Scanner<R> {
+        Scanner {
+            reader,
+            buffer: Vec::new(),
+            start: 0,
+            offset: 0,
+            eof: false,
+        }
+    }
+
+    // the instruction at `start`, or which kind of miss it was
+    fn read_at_start(&self) -> Result<(Instruction, usize), Miss> {
+        let memory = &self.buffer[self.start..];
+        for syntax in SYNTAX {
+            match read(memory, syntax) {
+                Err(Miss::NoMatch) => {}
+                Err(Miss::Incomplete) if self.eof => {}
+                // an earlier syntax takes precedence, so wait to see if it
+                // matches before trying the rest
+                result => return result,
+            }
+        }
+        Err(Miss::NoMatch)
+    }
+
+    // drops everything before `start` and reads another chunk after it
+    fn fill(&mut self) -> io::Result<()> {
+        self.buffer.drain(..self.start);
+        self.offset += self.start;
+        self.start = 0;
+
+        let len = self.buffer.len();
+        self.buffer.resize(len + Self::CHUNK, 0);
+        let read = loop {
+            match self.reader.read(&mut self.buffer[len..]) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                result => break result,
+            }
+        };
+        match read {
+            Ok(n) => {
+                self.buffer.truncate(len + n);
+                self.eof = n == 0;
+                Ok(())
+            }
+            Err(e) => {
+                self.buffer.truncate(len);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Scanner<R> {
+    type Item = io::Result<(usize, Instruction)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while self.start < self.buffer.len() {
+                match self.read_at_start() {
+                    Ok((instruction, len)) => {
+                        let offset = self.offset + self.start;
+                        self.start += len;
+                        return Some(Ok((offset, instruction)));
+                    }
+                    Err(Miss::NoMatch) => self.start += 1,
+                    Err(Miss::Incomplete) => break,
+                }
+            }
+            if self.eof {
+                return None;
+            }
+            if let Err(e) = self.fill() {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Every well-formed instruction in `memory`; see [`Scanner`].
+pub fn lex(memory: &str) -> Vec<(usize, Instruction)> {
+    Scanner::new(memory.as_bytes())
+        .collect::<io::Result<_>>()
+        .expect("reading from a slice never fails")
+}
+
+/// Why a near miss wasn't read as an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rejection {
+    UnknownName,
+    Whitespace,
+    WrongBrackets,
+    LongOperand,
+    WrongArity,
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            Rejection::UnknownName => "unknown name",
+            Rejection::Whitespace => "whitespace",
+            Rejection::WrongBrackets => "wrong brackets",
+            Rejection::LongOperand => "operand over 3 digits",
+            Rejection::WrongArity => "wrong number of operands",
+        };
+        write!(f, "{reason}")
+    }
+}
+
+/// Something shaped like an instruction that the lexer skipped, e.g.
+/// `mul[3,7]` or `mul ( 2, 4 )`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NearMiss {
+    pub offset: usize,
+    pub text: String,
+    pub reasons: Vec<Rejection>,
+    /// What lenient lexing reads it as, if anything
+    pub instruction: Option<Instruction>,
+}
+
+impl Display for NearMiss {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {:?} {}",
+            self.offset,
+            self.text,
+            self.reasons.iter().join(", ")
+        )?;
+        match self.instruction {
+            Some(instruction) => write!(f, " (lenient: {instruction})"),
+            None => Ok(()),
+        }
+    }
+}
+
+// a word in memory, as names are made of
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || byte == b'_' || byte == b'\''
+}
+
+// skips whitespace from `i`, noting it if there was any
+fn skip_spaces(bytes: &[u8], mut i: usize, reasons: &mut Vec<Rejection>) -> usize {
+    let start = i;
+    while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+        i += 1;
+    }
+    if i > start {
+        reasons.push(Rejection::Whitespace);
+    }
+    i
+}
+
+// reads `name` followed by a loose argument list from the start of `memory`:
+// brackets of any kind around comma separated numbers of any length, with
+// whitespace allowed anywhere in between
+fn read_near_miss(memory: &str, offset: usize, name: &str) -> Option<NearMiss> {
+    let bytes = &memory.as_bytes()[offset..];
+    let mut reasons = Vec::new();
+    let mut i = skip_spaces(bytes, name.len(), &mut reasons);
+    let open = *bytes.get(i)?;
+    if !b"([{<".contains(&open) {
+        return None;
+    }
+    i = skip_spaces(bytes, i + 1, &mut reasons);
+
+    let mut operands = Vec::new();
+    while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+        let digits = bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if digits > 3 {
+            reasons.push(Rejection::LongOperand);
+        }
+        operands.push(&memory[offset + i..offset + i + digits]);
+        i = skip_spaces(bytes, i + digits, &mut reasons);
+        if bytes.get(i) != Some(&b',') {
+            break;
+        }
+        i = skip_spaces(bytes, i + 1, &mut reasons);
+        if !bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            return None;
+        }
+    }
+
+    let close = *bytes.get(i)?;
+    if !b")]}>".contains(&close) {
+        return None;
+    }
+    if (open, close) != (b'(', b')') {
+        reasons.push(Rejection::WrongBrackets);
+    }
+
+    let syntax = SYNTAX.iter().find(|syntax| syntax.name == name);
+    match syntax {
+        None => reasons.push(Rejection::UnknownName),
+        Some(syntax) if syntax.operands != operands.len() => reasons.push(Rejection::WrongArity),
+        Some(_) => {}
+    }
+    if reasons.is_empty() {
+        return None;
+    }
+    reasons.sort();
+    reasons.dedup();
+
+    let instruction = syntax.filter(|syntax| syntax.operands == operands.len());
+    let instruction = instruction.and_then(|syntax| {
+        let operands = operands.iter().map(|operand| operand.parse().ok());
+        Some((syntax.build)(&operands.collect::<Option<Vec<u32>>>()?))
+    });
+    Some(NearMiss {
+        offset,
+        text: memory[offset..offset + i + 1].to_owned(),
+        reasons,
+        instruction,
+    })
+}
+
+// a near miss starting at `offset`, either with a known name or with an
+// unknown word. Words ending in a known name are left for that name, so
+// `undo()` is the instruction `do()` rather than a near miss.
+fn near_miss(memory: &str, offset: usize) -> Option<NearMiss> {
+    let rest = &memory[offset..];
+    let known = SYNTAX
+        .iter()
+        .filter(|syntax| rest.starts_with(syntax.name))
+        .find_map(|syntax| read_near_miss(memory, offset, syntax.name));
+    if known.is_some() {
+        return known;
+    }
+
+    let bytes = memory.as_bytes();
+    if offset > 0 && is_name_byte(bytes[offset - 1]) {
+        return None;
+    }
+    let len = rest.bytes().take_while(|&b| is_name_byte(b)).count();
+    let word = &rest[..len];
+    if len == 0 || SYNTAX.iter().any(|syntax| word.ends_with(syntax.name)) {
+        return None;
+    }
+    read_near_miss(memory, offset, word)
+}
+
+// what's at each position of `memory`, as far as linting is concerned
+enum Token {
+    Instruction(usize, Instruction),
+    NearMiss(NearMiss),
+}
+
+// walks `memory` like the lexer, also picking out near misses where there is
+// no instruction. Nothing else can start inside a near miss, so the
+// instructions found are just those [`lex`] finds.
+fn tokens(memory: &str) -> Vec<Token> {
+    let bytes = memory.as_bytes();
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let found = SYNTAX
+            .iter()
+            .find_map(|syntax| read(&bytes[offset..], syntax).ok());
+        if let Some((instruction, len)) = found {
+            tokens.push(Token::Instruction(offset, instruction));
+            offset += len;
+        } else if let Some(near_miss) = memory
+            .is_char_boundary(offset)
+            .then(|| near_miss(memory, offset))
+            .flatten()
+        {
+            offset += near_miss.text.len();
+            tokens.push(Token::NearMiss(near_miss));
+        } else {
+            offset += 1;
+        }
+    }
+    tokens
+}
+
+/// The instructions lexed from memory, and any near misses found along the
+/// way.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Program {
+    pub instructions: Vec<(usize, Instruction)>,
+    pub near_misses: Vec<NearMiss>,
+}
+
+/// Lexes `memory` along with its near misses. If `lenient`, near misses
+/// with a known name and the right number of operands are read as
+/// instructions too, whatever their brackets, spacing or operand lengths.
+pub fn lint(memory: &str, lenient: bool) -> Program {
+    let mut program = Program::default();
+    for token in tokens(memory) {
+        match token {
+            Token::Instruction(offset, instruction) => {
+                program.instructions.push((offset, instruction))
+            }
+            Token::NearMiss(near_miss) => {
+                if let Some(instruction) = near_miss.instruction.filter(|_| lenient) {
+                    program.instructions.push((near_miss.offset, instruction));
+                }
+                program.near_misses.push(near_miss);
+            }
+        }
+    }
+    program
+}
+
+/// The sum of the enabled `mul`s read from `reader`, without holding all of
+/// it in memory at once.
+pub fn sum(reader: impl Read, conditionals: bool) -> Result<u64> {
+    let mut vm = Vm::new(conditionals);
+    for instruction in Scanner::new(reader) {
+        let (offset, instruction) = instruction?;
+        vm.step(offset, instruction)?;
+    }
+    Ok(vm.accumulator)
+}
+
+/// The running total went past `u64::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    /// Byte offset of the `mul` that overflowed
+    pub offset: usize,
+    pub total: u64,
+    pub product: u64,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "running total overflowed at byte {}: {} + {}",
+            self.offset, self.total, self.product
+        )
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// One executed instruction, for comparing runs line by line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Step {
+    pub offset: usize,
+    pub instruction: Instruction,
+    /// Whether `mul`s were enabled when the instruction was reached
+    pub enabled: bool,
+    /// The running total after it
+    pub total: u64,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let enabled = if self.enabled { "on" } else { "off" };
+        write!(
+            f,
+            "{} {} {enabled} {}",
+            self.offset, self.instruction, self.total
+        )
+    }
+}
+
+/// Runs instructions, keeping an accumulator and whether `mul`s are enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vm {
+    pub enabled: bool,
+    pub accumulator: u64,
+    /// Whether `do()` and `don't()` have any effect
+    pub conditionals: bool,
+}
+
+impl Vm {
+    pub fn new(conditionals: bool) -> Vm {
+        Vm {
+            enabled: true,
+            accumulator: 0,
+            conditionals,
+        }
+    }
+
+    /// Runs the instruction found at byte `offset`.
+    pub fn step(&mut self, offset: usize, instruction: Instruction) -> Result<Step, Overflow> {
+        let enabled = self.enabled;
+        match instruction {
+            Instruction::Mul(a, b) if self.enabled => {
+                let product = a as u64 * b as u64;
+                self.accumulator = self.accumulator.checked_add(product).ok_or(Overflow {
+                    offset,
+                    total: self.accumulator,
+                    product,
+                })?;
+            }
+            Instruction::Mul(..) => {}
+            Instruction::Do => self.enabled = true,
+            Instruction::Dont => self.enabled = !self.conditionals,
+        }
+        Ok(Step {
+            offset,
+            instruction,
+            enabled,
+            total: self.accumulator,
+        })
+    }
+
+    pub fn run(mut self, instructions: &[(usize, Instruction)]) -> Result<u64, Overflow> {
+        for &(offset, instruction) in instructions {
+            self.step(offset, instruction)?;
+        }
+        Ok(self.accumulator)
+    }
+
+    /// Every step of a run, stopping at the first overflow.
+    pub fn trace(mut self, instructions: &[(usize, Instruction)]) -> Result<Vec<Step>, Overflow> {
+        instructions
+            .iter()
+            .map(|&(offset, instruction)| self.step(offset, instruction))
+            .collect()
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Mul(a, b) => write!(f, "mul({a},{b})"),
+            Instruction::Do => write!(f, "do()"),
+            Instruction::Dont => write!(f, "don't()"),
+        }
+    }
+}
+
+// as it would be written in memory
+impl Serialize for Instruction {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+// how many bytes of `memory` the instruction at `offset` was read from,
+// which for a near miss read leniently can be more than it prints as
+fn span(memory: &str, program: &Program, offset: usize) -> usize {
+    if let Some(near_miss) = program.near_misses.iter().find(|m| m.offset == offset) {
+        return near_miss.text.len();
+    }
+    SYNTAX
+        .iter()
+        .find_map(|syntax| read(&memory.as_bytes()[offset..], syntax).ok())
+        .map_or(0, |(_, len)| len)
+}
+
+// every `mul` counts without conditionals, so if that total fits then so
+// does any other
+fn check(input: &str, program: Program) -> Result<Program, ParseError> {
+    if let Err(overflow) = Vm::new(false).run(&program.instructions) {
+        return Err(ParseError::new(
+            input,
+            overflow.offset,
+            span(input, &program, overflow.offset),
+            overflow.to_string(),
+        ));
+    }
+    Ok(program)
+}
+
+fn parse(input: &str) -> Result<Program, ParseError> {
+    let instructions = lex(input);
+    check(
+        input,
+        Program {
+            instructions,
+            near_misses: Vec::new(),
+        },
+    )
+}
+
+fn part1(program: &Program) -> u64 {
+    Vm::new(false)
+        .run(&program.instructions)
+        .expect("checked for overflow in parse")
+}
+
+fn part2(program: &Program) -> u64 {
+    Vm::new(true)
+        .run(&program.instructions)
+        .expect("checked for overflow in parse")
+}
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+
+    type Input = Program;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    /// Options: `lenient` (default false) reads near misses as instructions
+    /// where it can, and `lint` (default false) finds them for [`explain`].
+    ///
+    /// [`explain`]: Solution::explain
+    fn parse_with(input: &str, options: &Options) -> Result<Self::Input> {
+        let lenient = options.get_or("lenient", false)?;
+        if !lenient && !options.get_or("lint", false)? {
+            return Ok(parse(input)?);
+        }
+        Ok(check(input, lint(input, lenient))?)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input)
+    }
+
+    /// A trace of every instruction run, or with `lint` set, every near
+    /// miss. Options: `conditionals` (default true, as in part 2) and
+    /// `format` (`text`, the default, or `json`).
+    fn explain(input: &Self::Input, options: &Options) -> Result<Option<String>> {
+        let json = match options.get_or("format", "text".to_owned())?.as_str() {
+            "text" => false,
+            "json" => true,
+            format => bail!("unknown format `{format}`, expected `text` or `json`"),
+        };
+        if options.get_or("lint", false)? {
+            if json {
+                return Ok(Some(
+                    serde_json::to_string_pretty(&input.near_misses)? + "\n",
+                ));
+            }
+            return Ok(Some(
+                input
+                    .near_misses
+                    .iter()
+                    .map(|near_miss| format!("{near_miss}\n"))
+                    .collect(),
+            ));
+        }
+
+        let trace = Vm::new(options.get_or("conditionals", true)?).trace(&input.instructions)?;
+        if json {
+            return Ok(Some(serde_json::to_string_pretty(&trace)? + "\n"));
+        }
+        Ok(Some(trace.iter().map(|step| format!("{step}\n")).collect()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+    const INPUT2: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    use super::*;
+    use crate::generate::{self, Rng};
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse(INPUT).unwrap()), 161);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse(INPUT2).unwrap()), 48);
+    }
+
+    fn operand(s: &str) -> Option<(u32, &str)> {
+        let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+        (1..=3)
+            .contains(&digits)
+            .then(|| (s[..digits].parse().unwrap(), &s[digits..]))
+    }
+
+    // every `mul(a,b)` found by trying each position in turn, with `do` and
+    // `don't` applied when `conditionals` is set
+    fn scan(memory: &str, conditionals: bool) -> u64 {
+        let mut enabled = true;
+        let mut total = 0;
+        for i in 0..memory.len() {
+            let rest = &memory[i..];
+            if rest.starts_with("do()") {
+                enabled = true;
+            } else if rest.starts_with("don't()") {
+                enabled = !conditionals;
+            } else if let Some(rest) = rest.strip_prefix("mul(") {
+                let Some((a, rest)) = operand(rest) else {
+                    continue;
+                };
+                let Some(rest) = rest.strip_prefix(',') else {
+                    continue;
+                };
+                let Some((b, rest)) = operand(rest) else {
+                    continue;
+                };
+                if rest.starts_with(')') && enabled {
+                    total += u64::from(a * b);
+                }
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn test_oracle() {
+        for seed in 0..200 {
+            let memory = generate::day3(&mut Rng::new(seed));
+            let input = parse(&memory).unwrap();
+            assert_eq!(part1(&input), scan(&memory, false), "seed {seed}");
+            assert_eq!(part2(&input), scan(&memory, true), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_lex() {
+        let instructions = lex("mul(mul(2,3)don't()xdo()mul(1234,1)mul(4,5))");
+        assert_eq!(
+            instructions,
+            [
+                (4, Instruction::Mul(2, 3)),
+                (12, Instruction::Dont),
+                (20, Instruction::Do),
+                (35, Instruction::Mul(4, 5)),
+            ]
+        );
+        assert_eq!(lex("mul(1,2"), []);
+        assert_eq!(lex("é mul(1,2)"), [(3, Instruction::Mul(1, 2))]);
+    }
+
+    // hands over one byte per read, so every instruction is split
+    struct OneByte<'a>(&'a [u8]);
+
+    impl Read for OneByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some((&byte, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = byte;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    fn scan_bytes(memory: &str) -> Vec<(usize, Instruction)> {
+        Scanner::new(OneByte(memory.as_bytes()))
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_scanner_one_byte() {
+        for memory in [
+            INPUT,
+            INPUT2,
+            "mul(1,2",
+            "mul(12,345)",
+            "do()don't(mul(1,1000)",
+        ] {
+            assert_eq!(scan_bytes(memory), lex(memory), "{memory}");
+        }
+        for seed in 0..200 {
+            let memory = generate::day3(&mut Rng::new(seed));
+            assert_eq!(scan_bytes(&memory), lex(&memory), "seed {seed}");
+            let sum = sum(OneByte(memory.as_bytes()), true).unwrap();
+            assert_eq!(sum, scan(&memory, true), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_scanner_chunks() {
+        // instructions straddling the end of the first chunk
+        let padding = "x".repeat(Scanner::<&[u8]>::CHUNK - 5);
+        let memory = format!("{padding}mul(11,22)do()mul(3,4)");
+        let instructions = Scanner::new(memory.as_bytes())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            instructions,
+            [
+                (padding.len(), Instruction::Mul(11, 22)),
+                (padding.len() + 10, Instruction::Do),
+                (padding.len() + 14, Instruction::Mul(3, 4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint() {
+        let program = lint(INPUT, false);
+        assert_eq!(program.instructions, lex(INPUT));
+        assert_eq!(
+            program.near_misses,
+            [
+                NearMiss {
+                    offset: 11,
+                    text: "mul[3,7]".to_owned(),
+                    reasons: vec![Rejection::WrongBrackets],
+                    instruction: Some(Instruction::Mul(3, 7)),
+                },
+                NearMiss {
+                    offset: 38,
+                    text: "mul(32,64]".to_owned(),
+                    reasons: vec![Rejection::WrongBrackets],
+                    instruction: Some(Instruction::Mul(32, 64)),
+                },
+            ]
+        );
+        assert_eq!(part1(&lint(INPUT, true)), 161 + 21 + 32 * 64);
+
+        let reasons = |memory: &str| {
+            let near_misses = lint(memory, false).near_misses;
+            assert_eq!(near_misses.len(), 1, "{memory}");
+            (near_misses[0].reasons.clone(), near_misses[0].instruction)
+        };
+        use Rejection::*;
+        assert_eq!(
+            reasons("mul ( 2, 4 )"),
+            (vec![Whitespace], Some(Instruction::Mul(2, 4)))
+        );
+        assert_eq!(
+            reasons("mul(1234,5)"),
+            (vec![LongOperand], Some(Instruction::Mul(1234, 5)))
+        );
+        assert_eq!(reasons("mul(99999999999,1)"), (vec![LongOperand], None));
+        assert_eq!(
+            reasons("don't ()"),
+            (vec![Whitespace], Some(Instruction::Dont))
+        );
+        assert_eq!(reasons("what()"), (vec![UnknownName], None));
+        assert_eq!(reasons("do(1)"), (vec![WrongArity], None));
+        assert_eq!(
+            reasons("mul{1 ,2>"),
+            (
+                vec![Whitespace, WrongBrackets],
+                Some(Instruction::Mul(1, 2))
+            )
+        );
+        assert_eq!(lint("undo()mul(4,", false).near_misses, []);
+    }
+
+    #[test]
+    fn test_lint_agrees() {
+        for seed in 0..200 {
+            let memory = generate::day3(&mut Rng::new(seed));
+            assert_eq!(
+                lint(&memory, false).instructions,
+                lex(&memory),
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_vm() {
+        let mut vm = Vm::new(true);
+        vm.step(0, Instruction::Mul(2, 3)).unwrap();
+        vm.step(8, Instruction::Dont).unwrap();
+        vm.step(15, Instruction::Mul(5, 5)).unwrap();
+        assert_eq!((vm.enabled, vm.accumulator), (false, 6));
+        vm.step(23, Instruction::Do).unwrap();
+        vm.step(27, Instruction::Mul(1, 4)).unwrap();
+        assert_eq!((vm.enabled, vm.accumulator), (true, 10));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut vm = Vm::new(false);
+        vm.accumulator = u64::MAX - 10;
+        vm.step(0, Instruction::Mul(2, 5)).unwrap();
+        let overflow = vm.step(9, Instruction::Mul(1, 1)).unwrap_err();
+        assert_eq!(
+            overflow.to_string(),
+            format!("running total overflowed at byte 9: {} + 1", u64::MAX)
+        );
+        assert_eq!(vm.accumulator, u64::MAX);
+    }
+
+    #[test]
+    fn test_overflow_span() {
+        let input = "mul(4294967295,4294967295)x\nmul( 4294967295,4294967295 )";
+        let error = check(input, lint(input, true)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.len, "mul( 4294967295,4294967295 )".len());
+
+        let program = Program {
+            instructions: vec![(0, Instruction::Mul(2, 3))],
+            near_misses: Vec::new(),
+        };
+        assert_eq!(span("mul(2,3)", &program, 0), 8);
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = parse(INPUT2).unwrap();
+        let trace = Day3::explain(&input, &Options::new()).unwrap().unwrap();
+        assert_eq!(
+            trace,
+            "1 mul(2,4) on 8\n20 don't() on 8\n28 mul(5,5) off 8\n48 mul(11,8) off 8\n59 do() off 8\n64 mul(8,5) on 48\n"
+        );
+
+        let options = Options::new()
+            .with("conditionals", "false")
+            .with("format", "json");
+        let json = Day3::explain(&input, &options).unwrap().unwrap();
+        let steps: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(steps[2]["instruction"], "mul(5,5)");
+        assert_eq!(steps[5]["total"], 161);
+    }
+}
@@ -0,0 +1,978 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Result};
+use itertools::Itertools;
+
+use crate::{
+    ansi::{self, Style},
+    grid::{Direction, Grid, Point},
+    Options, ParseError, Solution,
+};
+
+pub struct Day4;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    next: HashMap<char, usize>,
+    /// The node for the longest proper suffix of this one that is also in
+    /// the trie
+    fail: usize,
+    /// Every word ending here, including those that are suffixes of others
+    words: Vec<usize>,
+}
+
+/// A word list compiled for finding every word in a line of text in one
+/// pass (Aho-Corasick). Empty words are left out, as they can't be found
+/// anywhere in particular.
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    words: Vec<String>,
+    nodes: Vec<Node>,
+}
+
+impl Dictionary {
+    pub fn new<S: Into<String>>(words: impl IntoIterator<Item = S>) -> Dictionary {
+        let words = words
+            .into_iter()
+            .map(Into::into)
+            .filter(|word: &String| !word.is_empty())
+            .unique()
+            .collect_vec();
+
+        let mut nodes = vec![Node::default()];
+        for (i, word) in words.iter().enumerate() {
+            let mut node = 0;
+            for c in word.chars() {
+                node = match nodes[node].next.get(&c) {
+                    Some(&next) => next,
+                    None => {
+                        nodes.push(Node::default());
+                        let next = nodes.len() - 1;
+                        nodes[node].next.insert(c, next);
+                        next
+                    }
+                };
+            }
+            nodes[node].words.push(i);
+        }
+
+        // breadth first, so every node's fail link is finished before its
+        // children need it
+        let mut queue = nodes[0].next.values().copied().collect::<VecDeque<_>>();
+        while let Some(node) = queue.pop_front() {
+            let children = nodes[node]
+                .next
+                .iter()
+                .map(|(&c, &child)| (c, child))
+                .collect_vec();
+            for (c, child) in children {
+                let fail = Dictionary::step(&nodes, nodes[node].fail, c);
+                let fail = if fail == child { 0 } else { fail };
+                nodes[child].fail = fail;
+                let inherited = nodes[fail].words.clone();
+                nodes[child].words.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        Dictionary { words, nodes }
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    // follows fail links from `node` until one can take `c`
+    fn step(nodes: &[Node], mut node: usize, c: char) -> usize {
+        loop {
+            if let Some(&next) = nodes[node].next.get(&c) {
+                return next;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = nodes[node].fail;
+        }
+    }
+
+    /// Every word in `line`, as the index of the word and the index of the
+    /// character it ends on.
+    pub fn find_in(&self, line: impl IntoIterator<Item = char>) -> Vec<(usize, usize)> {
+        let mut node = 0;
+        let mut found = Vec::new();
+        for (i, c) in line.into_iter().enumerate() {
+            node = Dictionary::step(&self.nodes, node, c);
+            found.extend(self.nodes[node].words.iter().map(|&word| (word, i)));
+        }
+        found
+    }
+}
+
+/// What happens to a word that reaches the edge of the grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Edges {
+    /// It stops there, as in the puzzle.
+    #[default]
+    Bounded,
+    /// It carries on from the opposite edge.
+    Toroidal,
+    /// It bounces back off the edge, without reading the edge cell twice.
+    Reflecting,
+}
+
+impl FromStr for Edges {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Edges, String> {
+        match s {
+            "bounded" => Ok(Edges::Bounded),
+            "toroidal" => Ok(Edges::Toroidal),
+            "reflecting" => Ok(Edges::Reflecting),
+            _ => Err("expected `bounded`, `toroidal` or `reflecting`".to_owned()),
+        }
+    }
+}
+
+// one coordinate of a step from `at` by `delta` along an axis of length `len`,
+// and the delta to carry on with
+fn step_axis(edges: Edges, at: isize, delta: isize, len: usize) -> Option<(isize, isize)> {
+    let len = len as isize;
+    let next = at + delta;
+    if (0..len).contains(&next) {
+        return Some((next, delta));
+    }
+    match edges {
+        Edges::Bounded => None,
+        Edges::Toroidal => Some((next.rem_euclid(len), delta)),
+        Edges::Reflecting if len == 1 => Some((at, -delta)),
+        Edges::Reflecting => Some((at - delta, -delta)),
+    }
+}
+
+impl Edges {
+    /// The cells read from `start` heading in `direction`, for as long as
+    /// the edges allow. Only a bounded path ever ends.
+    pub fn path<T>(
+        self,
+        grid: &Grid<T>,
+        start: Point,
+        direction: Direction,
+    ) -> impl Iterator<Item = Point> + '_ {
+        let first = grid.in_bounds(start).then_some((start, direction));
+        std::iter::successors(first, move |&(point, direction)| {
+            let (x, dx) = step_axis(self, point.x, direction.dx, grid.width())?;
+            let (y, dy) = step_axis(self, point.y, direction.dy, grid.height())?;
+            Some((Point::new(x, y), Direction::new(dx, dy)))
+        })
+        .map(|(point, _)| point)
+    }
+}
+
+/// Where and how [`search_with`] looks for words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub edges: Edges,
+    /// The directions words may be read in
+    pub directions: Vec<Direction>,
+    /// Words longer than this are left out
+    pub max_len: Option<usize>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> SearchOptions {
+        SearchOptions {
+            edges: Edges::Bounded,
+            directions: Direction::ALL.to_vec(),
+            max_len: None,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Reads `edges`, `directions` (comma separated, e.g. `E,SE,S`) and
+    /// `max_len`.
+    pub fn from_options(options: &Options) -> Result<SearchOptions> {
+        let mut search = SearchOptions {
+            edges: options.get_or("edges", Edges::Bounded)?,
+            max_len: options.get("max_len")?,
+            ..SearchOptions::default()
+        };
+        if let Some(directions) = options.get::<String>("directions")? {
+            search.directions = directions
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<_, String>>()
+                .map_err(|e| anyhow!("option `directions`: {e}"))?;
+        }
+        Ok(search)
+    }
+}
+
+/// A word found in the grid, reading from `start` in `direction` and
+/// carrying on past the edges as `edges` says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Match<'a> {
+    pub word: &'a str,
+    pub start: Point,
+    pub direction: Direction,
+    pub edges: Edges,
+}
+
+/// Every word from `dictionary` in `grid`, reading in all eight directions
+/// and stopping at the edges.
+pub fn search<'a>(grid: &Grid<char>, dictionary: &'a Dictionary) -> Vec<Match<'a>> {
+    search_with(grid, dictionary, &SearchOptions::default())
+}
+
+/// Every word from `dictionary` in `grid`, read as `options` allow. Bounded
+/// and toroidal grids are split into lines (or loops) in each direction,
+/// and each is scanned once whatever the number of words. Reflected paths
+/// cross each other, so those are read from each start cell in turn.
+/// Matches are in reading order of their start cells.
+pub fn search_with<'a>(
+    grid: &Grid<char>,
+    dictionary: &'a Dictionary,
+    options: &SearchOptions,
+) -> Vec<Match<'a>> {
+    let len = |word: usize| dictionary.words[word].chars().count();
+    let allowed = |word: usize| options.max_len.is_none_or(|max| len(word) <= max);
+    let longest = (0..dictionary.words.len())
+        .filter(|&word| allowed(word))
+        .map(len)
+        .max()
+        .unwrap_or(0);
+
+    let mut matches = Vec::new();
+    for &direction in &options.directions {
+        // words in `line` starting in its first `starts` cells
+        let mut found = |line: &[Point], starts: usize| {
+            let chars = line.iter().map(|&point| grid[point]);
+            for (word, end) in dictionary.find_in(chars) {
+                let start = end + 1 - len(word);
+                if start < starts && allowed(word) {
+                    matches.push(Match {
+                        word: &dictionary.words[word],
+                        start: line[start],
+                        direction,
+                        edges: options.edges,
+                    });
+                }
+            }
+        };
+
+        match options.edges {
+            Edges::Bounded => {
+                let starts = grid
+                    .points()
+                    .filter(|&point| !grid.in_bounds(point + direction.reverse()));
+                for start in starts {
+                    let line = grid.ray(start, direction).collect_vec();
+                    found(&line, line.len());
+                }
+            }
+            Edges::Toroidal => {
+                let mut seen = HashSet::new();
+                for start in grid.points() {
+                    if seen.contains(&start) {
+                        continue;
+                    }
+                    let path = Edges::Toroidal.path(grid, start, direction);
+                    let cycle = path.take_while(|&point| seen.insert(point)).count();
+                    // go round far enough for words starting near the end
+                    let line = Edges::Toroidal
+                        .path(grid, start, direction)
+                        .take(cycle + longest.saturating_sub(1))
+                        .collect_vec();
+                    found(&line, cycle);
+                }
+            }
+            Edges::Reflecting => {
+                for start in grid.points() {
+                    let line = Edges::Reflecting
+                        .path(grid, start, direction)
+                        .take(longest)
+                        .collect_vec();
+                    found(&line, 1);
+                }
+            }
+        }
+    }
+    matches.sort_by_key(|m| (m.start.y, m.start.x, m.direction, m.word));
+    matches
+}
+
+impl Match<'_> {
+    /// The cells the word covers, from its first letter to its last.
+    pub fn cells<'a, T>(&'a self, grid: &'a Grid<T>) -> impl Iterator<Item = Point> + 'a {
+        self.edges
+            .path(grid, self.start, self.direction)
+            .take(self.word.chars().count())
+    }
+}
+
+/// How [`highlight`] shows a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// Matched cells as they are, unmatched ones as `.`
+    Text,
+    /// Matched cells in colour, unmatched ones dimmed, or as `.` if `hide`
+    Ansi { hide: bool },
+    /// As for `Ansi`, but as an HTML `<pre>` block
+    Html { hide: bool },
+}
+
+/// `grid` with the cells of each match picked out. Each match gets its own
+/// colour, with matches from the same start cell always coloured apart (up
+/// to the size of the palette). Cells in more than one match are also bold.
+pub fn highlight(grid: &Grid<char>, matches: &[Match], mode: Highlight) -> String {
+    let mut styles = HashMap::new();
+    let mut origins = HashMap::new();
+    for m in matches {
+        let next = origins.len();
+        let (origin, count) = origins.entry(m.start).or_insert((next, 0));
+        let color = Style::PALETTE[(*origin + *count) % Style::PALETTE.len()];
+        *count += 1;
+        for point in m.cells(grid) {
+            styles
+                .entry(point)
+                .and_modify(|(_, overlaps)| *overlaps += 1)
+                .or_insert((color, 0));
+        }
+    }
+
+    let mut out = String::new();
+    if let Highlight::Html { .. } = mode {
+        out.push_str("<pre class=\"word-search\">\n");
+    }
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let point = Point::from((x, y));
+            let c = grid[point];
+            let cell = match (mode, styles.get(&point)) {
+                (Highlight::Text, Some(_)) => c.to_string(),
+                (Highlight::Text, None) => ".".to_owned(),
+                (Highlight::Ansi { .. }, Some(&(color, overlaps))) => match overlaps {
+                    0 => color.paint(c),
+                    _ => Style::BOLD.paint(color.paint(c)),
+                },
+                (Highlight::Ansi { hide: true }, None) => ".".to_owned(),
+                (Highlight::Ansi { hide: false }, None) => Style::DIM.paint(c),
+                (Highlight::Html { .. }, Some(&(color, overlaps))) => {
+                    let style = match overlaps {
+                        0 => color.css().to_owned(),
+                        _ => format!("{}; {}", color.css(), Style::BOLD.css()),
+                    };
+                    format!("<span style=\"{style}\">{}</span>", escape(c))
+                }
+                (Highlight::Html { hide: true }, None) => ".".to_owned(),
+                (Highlight::Html { hide: false }, None) => {
+                    format!("<span style=\"{}\">{}</span>", Style::DIM.css(), escape(c))
+                }
+            };
+            out.push_str(&cell);
+        }
+        out.push('\n');
+    }
+    if let Highlight::Html { .. } = mode {
+        out.push_str("</pre>\n");
+    }
+    out
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '<' => "&lt;".to_owned(),
+        '>' => "&gt;".to_owned(),
+        '&' => "&amp;".to_owned(),
+        '"' => "&quot;".to_owned(),
+        c => c.to_string(),
+    }
+}
+
+/// One cell of a [`Pattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cell {
+    /// `?`: anything at all
+    Any,
+    /// A single character, or `[...]` for any one of several
+    OneOf(Vec<char>),
+}
+
+impl Cell {
+    pub fn matches(&self, c: char) -> bool {
+        match self {
+            Cell::Any => true,
+            Cell::OneOf(chars) => chars.contains(&c),
+        }
+    }
+}
+
+/// A small 2D stencil to look for in a grid, in any of its variants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    variants: Vec<Grid<Cell>>,
+}
+
+/// The X-MAS: two `MAS`es crossing at the `A`, in any of four rotations.
+pub const X_MAS: &str = "M?M
+?A?
+S?S";
+
+// a quarter turn clockwise: the left column becomes the top row
+fn rotate(stencil: &Grid<Cell>) -> Grid<Cell> {
+    let (width, height) = (stencil.width(), stencil.height());
+    let cells = (0..width)
+        .flat_map(|y| (0..height).map(move |x| Point::from((y, height - 1 - x))))
+        .map(|point| stencil[point].clone())
+        .collect();
+    Grid::new(height, width, cells)
+}
+
+/// Where a [`Pattern`] was found: the top left corner of the variant that
+/// matched, and which one it was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PatternMatch {
+    pub position: Point,
+    pub variant: usize,
+}
+
+impl Pattern {
+    /// Reads a stencil with one row per line. Each cell is `?` for any
+    /// character, `[...]` for any one of the characters inside, or a
+    /// character to match exactly.
+    pub fn parse(stencil: &str) -> Result<Pattern, ParseError> {
+        let mut rows = Vec::new();
+        for line in stencil.lines() {
+            let mut row = Vec::new();
+            let mut chars = line.char_indices();
+            while let Some((i, c)) = chars.next() {
+                row.push(match c {
+                    '?' => Cell::Any,
+                    '[' => {
+                        let Some(len) = line[i..].find(']') else {
+                            return Err(ParseError::at(stencil, &line[i..], "unclosed `[`"));
+                        };
+                        let set = line[i + 1..i + len].chars().collect_vec();
+                        if set.is_empty() {
+                            let token = &line[i..i + len + 1];
+                            return Err(ParseError::at(stencil, token, "empty set"));
+                        }
+                        chars.nth(set.len());
+                        Cell::OneOf(set)
+                    }
+                    c => Cell::OneOf(vec![c]),
+                });
+            }
+            rows.push((line, row));
+        }
+
+        let width = rows.first().map_or(0, |(_, row)| row.len());
+        if width == 0 {
+            return Err(ParseError::new(stencil, 0, 0, "empty pattern"));
+        }
+        if let Some((line, row)) = rows.iter().find(|(_, row)| row.len() != width) {
+            let message = format!("expected {width} cells, found {}", row.len());
+            return Err(ParseError::at(stencil, line, message));
+        }
+        let height = rows.len();
+        let cells = rows.into_iter().flat_map(|(_, row)| row).collect();
+        Ok(Pattern {
+            variants: vec![Grid::new(width, height, cells)],
+        })
+    }
+
+    /// Also matches every variant turned by a quarter, half and three
+    /// quarter turn.
+    pub fn with_rotations(self) -> Pattern {
+        self.with(rotate).with(rotate).with(rotate)
+    }
+
+    /// Also matches the mirror image of every variant.
+    pub fn with_reflections(self) -> Pattern {
+        self.with(|stencil| {
+            let width = stencil.width();
+            let cells = stencil
+                .points()
+                .map(|point| stencil[Point::new(width as isize - 1 - point.x, point.y)].clone())
+                .collect();
+            Grid::new(width, stencil.height(), cells)
+        })
+    }
+
+    // adds `transform` of every variant, leaving out any already there
+    fn with(mut self, transform: fn(&Grid<Cell>) -> Grid<Cell>) -> Pattern {
+        for i in 0..self.variants.len() {
+            let variant = transform(&self.variants[i]);
+            if !self.variants.contains(&variant) {
+                self.variants.push(variant);
+            }
+        }
+        self
+    }
+
+    /// The distinct stencils this pattern matches, the original first.
+    pub fn variants(&self) -> &[Grid<Cell>] {
+        &self.variants
+    }
+
+    /// Every placement of every variant that matches `grid`, in reading
+    /// order.
+    pub fn find(&self, grid: &Grid<char>) -> Vec<PatternMatch> {
+        let mut found = Vec::new();
+        for (variant, stencil) in self.variants.iter().enumerate() {
+            let fits = |start: &Point| {
+                grid.in_bounds(Point::new(
+                    start.x + stencil.width() as isize - 1,
+                    start.y + stencil.height() as isize - 1,
+                ))
+            };
+            for position in grid.points().filter(fits) {
+                let matches = stencil.enumerate().all(|(offset, cell)| {
+                    cell.matches(grid[Point::new(position.x + offset.x, position.y + offset.y)])
+                });
+                if matches {
+                    found.push(PatternMatch { position, variant });
+                }
+            }
+        }
+        found.sort_by_key(|m| (m.position.y, m.position.x, m.variant));
+        found
+    }
+}
+
+fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(input, Some)
+}
+fn part1(grid: &Grid<char>, word: &str) -> u32 {
+    search(grid, &Dictionary::new([word])).len() as u32
+}
+
+fn part2(grid: &Grid<char>) -> u32 {
+    let x_mas = Pattern::parse(X_MAS).expect("X_MAS is a valid pattern");
+    x_mas.with_rotations().find(grid).len() as u32
+}
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+
+    type Input = Grid<char>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input, "XMAS")
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+
+    /// The grid with every found word picked out. Options: `words`, comma
+    /// separated (default `XMAS`), those of [`SearchOptions`], `format`
+    /// (`text`, the default, or `html`), `unmatched` (`dim`, the default, or
+    /// `hide`) and `color`.
+    fn explain(input: &Self::Input, options: &Options) -> Result<Option<String>> {
+        let words = options.get_or("words", "XMAS".to_owned())?;
+        let dictionary = Dictionary::new(words.split(',').map(str::trim));
+        let hide = match options.get_or("unmatched", "dim".to_owned())?.as_str() {
+            "dim" => false,
+            "hide" => true,
+            other => bail!("option `unmatched`: expected `dim` or `hide` (got `{other}`)"),
+        };
+        let mode = match options.get_or("format", "text".to_owned())?.as_str() {
+            "text" if ansi::enabled(options)? => Highlight::Ansi { hide },
+            "text" => Highlight::Text,
+            "html" => Highlight::Html { hide },
+            format => bail!("unknown format `{format}`, expected `text` or `html`"),
+        };
+        let matches = search_with(input, &dictionary, &SearchOptions::from_options(options)?);
+        Ok(Some(highlight(input, &matches, mode)))
+    }
+}
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    use super::*;
+    use crate::generate::{self, Rng};
+
+    #[test]
+    fn test_part1() {
+        let input = parse(INPUT).unwrap();
+        assert_eq!(part1(&input, "XMAS"), 18);
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = parse(INPUT).unwrap();
+        assert_eq!(part2(&input), 9);
+    }
+
+    #[test]
+    fn test_search() {
+        let grid = parse(INPUT).unwrap();
+        let dictionary = Dictionary::new(["XMAS", "SAMX", "MAS", ""]);
+        assert_eq!(dictionary.words(), ["XMAS", "SAMX", "MAS"]);
+        let matches = search(&grid, &dictionary);
+        let count = |word| matches.iter().filter(|m| m.word == word).count();
+        assert_eq!((count("XMAS"), count("SAMX"), count("MAS")), (18, 18, 38));
+        assert!(matches.contains(&Match {
+            word: "XMAS",
+            start: Point::new(5, 0),
+            direction: Direction::E,
+            edges: Edges::Bounded,
+        }));
+        assert!(matches.contains(&Match {
+            word: "XMAS",
+            start: Point::new(9, 3),
+            direction: Direction::S,
+            edges: Edges::Bounded,
+        }));
+    }
+
+    #[test]
+    fn test_search_oracle() {
+        for seed in 0..100 {
+            let mut rng = Rng::new(seed);
+            let grid = parse(&generate::day4(&mut rng)).unwrap();
+            let words = (0..rng.range(1, 6))
+                .map(|_| {
+                    (0..rng.range(1, 4))
+                        .map(|_| rng.pick(&['X', 'M', 'A', 'S']))
+                        .collect::<String>()
+                })
+                .collect_vec();
+            let dictionary = Dictionary::new(&words);
+
+            let mut expected = Vec::new();
+            for start in grid.points() {
+                for direction in Direction::ALL {
+                    for word in dictionary.words() {
+                        let ray = grid.ray(start, direction).map(|point| grid[point]);
+                        if ray.take(word.len()).eq(word.chars()) {
+                            expected.push(Match {
+                                word,
+                                start,
+                                direction,
+                                edges: Edges::Bounded,
+                            });
+                        }
+                    }
+                }
+            }
+            let mut found = search(&grid, &dictionary);
+            expected.sort();
+            found.sort();
+            assert_eq!(found, expected, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_pattern() {
+        let grid = parse(INPUT).unwrap();
+        let x_mas = Pattern::parse(X_MAS).unwrap().with_rotations();
+        assert_eq!(x_mas.variants().len(), 4);
+        let found = x_mas.find(&grid);
+        assert_eq!(found.len(), 9);
+        assert_eq!(found[0].position, Point::new(1, 0));
+
+        // the same shape as one stencil with sets, and no rotations
+        let sets = Pattern::parse("[MS]?[MS]\n?A?\n[MS]?[MS]").unwrap();
+        let crossing = sets
+            .find(&grid)
+            .into_iter()
+            .filter(|m| {
+                let at = |dx, dy| grid[Point::new(m.position.x + dx, m.position.y + dy)];
+                at(0, 0) != at(2, 2) && at(2, 0) != at(0, 2)
+            })
+            .map(|m| m.position)
+            .collect_vec();
+        assert_eq!(crossing, found.iter().map(|m| m.position).collect_vec());
+
+        let plus = Pattern::parse("?M?\nMAS\n?S?").unwrap();
+        assert_eq!(plus.clone().with_rotations().variants().len(), 4);
+        assert_eq!(plus.with_reflections().variants().len(), 2);
+        let symmetric = Pattern::parse("?M?\nMAM\n?M?").unwrap();
+        assert_eq!(
+            symmetric
+                .with_rotations()
+                .with_reflections()
+                .variants()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_pattern_shapes() {
+        let grid = parse("XMAS\nSAMX").unwrap();
+        let mas = Pattern::parse("MAS").unwrap().with_rotations();
+        assert_eq!(mas.variants().len(), 4);
+        assert_eq!(
+            mas.variants()[1],
+            Grid::parse("M\nA\nS", |c| Some(Cell::OneOf(vec![c]))).unwrap()
+        );
+        assert_eq!(
+            mas.find(&grid),
+            [
+                PatternMatch {
+                    position: Point::new(1, 0),
+                    variant: 0
+                },
+                PatternMatch {
+                    position: Point::new(0, 1),
+                    variant: 2
+                },
+            ]
+        );
+
+        let error = Pattern::parse("M?\n[MS\n").unwrap_err();
+        assert_eq!((error.message.as_str(), error.line), ("unclosed `[`", 2));
+        let error = Pattern::parse("M?\nM").unwrap_err();
+        assert_eq!(error.message, "expected 2 cells, found 1");
+        assert!(Pattern::parse("[]").is_err());
+        assert!(Pattern::parse("").is_err());
+    }
+
+    #[test]
+    fn test_edges_path() {
+        let grid = parse("abc\ndef").unwrap();
+        let path = |edges: Edges, start, direction| {
+            edges
+                .path(&grid, start, direction)
+                .take(5)
+                .map(|point| grid[point])
+                .collect::<String>()
+        };
+        assert_eq!(path(Edges::Bounded, Point::new(1, 0), Direction::E), "bc");
+        assert_eq!(
+            path(Edges::Toroidal, Point::new(1, 0), Direction::E),
+            "bcabc"
+        );
+        assert_eq!(
+            path(Edges::Toroidal, Point::new(2, 1), Direction::SE),
+            "faecd"
+        );
+        assert_eq!(
+            path(Edges::Reflecting, Point::new(1, 0), Direction::E),
+            "bcbab"
+        );
+        assert_eq!(
+            path(Edges::Reflecting, Point::new(2, 1), Direction::SE),
+            "fbdbf"
+        );
+        assert_eq!(
+            path(Edges::Reflecting, Point::new(0, 0), Direction::S),
+            "adada"
+        );
+        assert_eq!(path(Edges::Bounded, Point::new(3, 0), Direction::E), "");
+    }
+
+    #[test]
+    fn test_search_with() {
+        let grid = parse(INPUT).unwrap();
+        let dictionary = Dictionary::new(["XMAS"]);
+        let forward = vec![Direction::E, Direction::SE, Direction::S, Direction::NE];
+        let count = |edges, directions: &Vec<Direction>| {
+            let options = SearchOptions {
+                edges,
+                directions: directions.clone(),
+                max_len: None,
+            };
+            search_with(&grid, &dictionary, &options)
+        };
+        let all = Direction::ALL.to_vec();
+        assert_eq!(count(Edges::Bounded, &all).len(), 18);
+        assert_eq!(count(Edges::Bounded, &forward).len(), 9);
+        assert_eq!(count(Edges::Toroidal, &forward).len(), 11);
+        assert_eq!(count(Edges::Reflecting, &forward).len(), 17);
+
+        let toroidal = count(Edges::Toroidal, &all);
+        assert_eq!(toroidal.len(), 21);
+        let wrapped = Match {
+            word: "XMAS",
+            start: Point::new(5, 8),
+            direction: Direction::SE,
+            edges: Edges::Toroidal,
+        };
+        assert!(toroidal.contains(&wrapped));
+        let cells = wrapped.cells(&grid).collect_vec();
+        assert_eq!(cells[2], Point::new(7, 0));
+
+        let reflecting = count(Edges::Reflecting, &all);
+        assert_eq!(reflecting.len(), 33);
+        assert!(reflecting.contains(&Match {
+            word: "XMAS",
+            start: Point::new(0, 4),
+            direction: Direction::W,
+            edges: Edges::Reflecting,
+        }));
+
+        let dictionary = Dictionary::new(["XMAS", "MAS"]);
+        let options = Options::new()
+            .with("max_len", "3")
+            .with("directions", "e, s")
+            .with("edges", "toroidal");
+        let options = SearchOptions::from_options(&options).unwrap();
+        assert_eq!(options.directions, [Direction::E, Direction::S]);
+        let matches = search_with(&grid, &dictionary, &options);
+        assert!(!matches.is_empty());
+        assert!(matches.iter().all(|m| m.word == "MAS"));
+
+        let options = Options::new().with("directions", "E,up");
+        assert!(SearchOptions::from_options(&options).is_err());
+    }
+
+    #[test]
+    fn test_search_with_oracle() {
+        for seed in 0..100 {
+            let mut rng = Rng::new(seed);
+            let grid = parse(&generate::day4(&mut rng)).unwrap();
+            let words = (0..rng.range(1, 4))
+                .map(|_| {
+                    (0..rng.range(1, 5))
+                        .map(|_| rng.pick(&['X', 'M', 'A', 'S']))
+                        .collect::<String>()
+                })
+                .collect_vec();
+            let dictionary = Dictionary::new(&words);
+            let mut directions = Direction::ALL.to_vec();
+            rng.shuffle(&mut directions);
+            directions.truncate(rng.range(1, 8) as usize);
+
+            for edges in [Edges::Bounded, Edges::Toroidal, Edges::Reflecting] {
+                let options = SearchOptions {
+                    edges,
+                    directions: directions.clone(),
+                    max_len: Some(3),
+                };
+                let mut expected = Vec::new();
+                for start in grid.points() {
+                    for &direction in &directions {
+                        for word in dictionary.words().iter().filter(|word| word.len() <= 3) {
+                            let path = edges.path(&grid, start, direction);
+                            if path
+                                .map(|point| grid[point])
+                                .take(word.len())
+                                .eq(word.chars())
+                            {
+                                expected.push(Match {
+                                    word,
+                                    start,
+                                    direction,
+                                    edges,
+                                });
+                            }
+                        }
+                    }
+                }
+                let mut found = search_with(&grid, &dictionary, &options);
+                expected.sort();
+                found.sort();
+                assert_eq!(found, expected, "seed {seed}, {edges:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_highlight() {
+        let grid = parse("XMASX\nMMAAM\nAAAMA\nSSSSS").unwrap();
+        let dictionary = Dictionary::new(["XMAS"]);
+        let matches = search(&grid, &dictionary);
+        assert_eq!(matches.len(), 4);
+
+        let text = highlight(&grid, &matches, Highlight::Text);
+        assert_eq!(text, "XMASX\nMM..M\nA.A.A\nS..SS\n");
+
+        let ansi = highlight(&grid, &matches, Highlight::Ansi { hide: false });
+        assert_eq!(ansi::strip(&ansi), "XMASX\nMMAAM\nAAAMA\nSSSSS\n");
+        let first = ansi.lines().next().unwrap();
+        // the three words from the top left corner are coloured apart, and
+        // the `X` they share is bold
+        assert!(first.starts_with(&Style::BOLD.paint(Style::RED.paint('X'))));
+        assert!(first.contains(&Style::GREEN.paint('M')));
+        let second = ansi.lines().nth(1).unwrap();
+        assert!(second.starts_with(&Style::RED.paint('M')));
+        assert!(second.contains(&Style::YELLOW.paint('M')));
+        assert!(ansi.contains(&Style::DIM.paint('A')));
+        let hidden = highlight(&grid, &matches, Highlight::Ansi { hide: true });
+        assert_eq!(ansi::strip(&hidden), text);
+
+        let html = highlight(&grid, &matches, Highlight::Html { hide: true });
+        assert!(html.starts_with("<pre class=\"word-search\">\n<span style=\"color: #d70000; font-weight: bold\">X</span>"));
+        assert!(html.ends_with("<span style=\"color: #00875f\">S</span>\n</pre>\n"));
+    }
+
+    #[test]
+    fn test_explain() {
+        let grid = parse(INPUT).unwrap();
+        let options = Options::new()
+            .with("color", "never")
+            .with("words", "XMAS, SAMX");
+        let report = Day4::explain(&grid, &options).unwrap().unwrap();
+        assert_eq!(report.lines().next(), Some("....XXMAS."));
+        assert!(Day4::explain(&grid, &options.with("unmatched", "blur")).is_err());
+    }
+
+    #[test]
+    fn test_oracle() {
+        for seed in 0..200 {
+            let input = generate::day4(&mut Rng::new(seed));
+            let rows = input
+                .lines()
+                .map(|l| l.chars().collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let at = |x: isize, y: isize| -> Option<char> {
+                rows.get(usize::try_from(y).ok()?)?
+                    .get(usize::try_from(x).ok()?)
+                    .copied()
+            };
+            let (width, height) = (rows[0].len() as isize, rows.len() as isize);
+            let cells = (0..height).flat_map(|y| (0..width).map(move |x| (x, y)));
+
+            let mut xmas = 0;
+            for (x, y) in cells.clone() {
+                for Direction { dx, dy } in Direction::ALL {
+                    if "XMAS"
+                        .chars()
+                        .zip(0..)
+                        .all(|(c, i)| at(x + dx * i, y + dy * i) == Some(c))
+                    {
+                        xmas += 1;
+                    }
+                }
+            }
+            let x_mas = cells
+                .filter(|&(x, y)| {
+                    let diagonal = |a: (isize, isize), b: (isize, isize)| {
+                        let pair = [at(a.0, a.1), at(b.0, b.1)];
+                        pair == [Some('M'), Some('S')] || pair == [Some('S'), Some('M')]
+                    };
+                    at(x, y) == Some('A')
+                        && diagonal((x - 1, y - 1), (x + 1, y + 1))
+                        && diagonal((x + 1, y - 1), (x - 1, y + 1))
+                })
+                .count() as u32;
+
+            let grid = parse(&input).unwrap();
+            assert_eq!(part1(&grid, "XMAS"), xmas, "seed {seed}");
+            assert_eq!(part2(&grid), x_mas, "seed {seed}");
+        }
+    }
+}
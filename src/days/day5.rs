@@ -0,0 +1,373 @@
+use std::{cmp::Ordering, collections::BTreeSet, fmt::Display};
+
+use itertools::Itertools;
+use nom::{
+    bytes::complete::tag,
+    character::complete::newline,
+    combinator::{cut, map_res},
+    multi::{count, many0, separated_list1},
+    sequence::{preceded, separated_pair},
+    IResult,
+};
+
+use serde_json::json;
+
+use crate::{diagnostics, parse::finish, ParseError, Solution};
+
+pub struct Day5;
+
+type Rule = (u32, u32);
+
+fn digit1(input: &str) -> IResult<&str, u32> {
+    map_res(nom::character::complete::digit1, str::parse::<u32>)(input)
+}
+
+fn rule(input: &str) -> IResult<&str, Rule> {
+    separated_pair(digit1, tag("|"), cut(digit1))(input)
+}
+
+fn list(input: &str) -> IResult<&str, Vec<u32>> {
+    // `cut` after each comma so a bad page number is reported where it is,
+    // rather than as leftover input after the list
+    let (input, first) = digit1(input)?;
+    let (input, mut pages) = many0(preceded(tag(","), cut(digit1)))(input)?;
+    pages.insert(0, first);
+    Ok((input, pages))
+}
+
+/// An update's pages in an order the rules allow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ordered {
+    pub pages: Vec<u32>,
+    /// If the rules allow more than one order, the first pages that could
+    /// have gone in any order among themselves
+    pub ambiguous: Option<Vec<u32>>,
+}
+
+/// Pages whose rules go round in a circle, each before the next and the
+/// last before the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle(pub Vec<u32>);
+
+impl Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pages = self.0.iter().chain(self.0.first()).join(" -> ");
+        write!(f, "pages must come before themselves: {pages}")
+    }
+}
+
+/// Puts `pages` in order, using only the rules between them (Kahn's
+/// algorithm). Where the rules leave a choice, pages keep the order they
+/// were given in, so an update that already follows the rules comes back
+/// as it was. Repeated pages are all kept, so the order is as long as the
+/// update.
+pub fn order(rules: &[Rule], pages: &[u32]) -> Result<Ordered, Cycle> {
+    let index = pages
+        .iter()
+        .copied()
+        .enumerate()
+        .into_group_map_by(|&(_, p)| p);
+
+    let mut after = vec![Vec::new(); pages.len()];
+    let mut before_count = vec![0; pages.len()];
+    for (a, b) in rules {
+        if let (Some(a), Some(b)) = (index.get(a), index.get(b)) {
+            for (&(a, _), &(b, _)) in a.iter().cartesian_product(b) {
+                after[a].push(b);
+                before_count[b] += 1;
+            }
+        }
+    }
+
+    // ready pages, kept in the order they were given
+    let mut ready = (0..pages.len())
+        .filter(|&i| before_count[i] == 0)
+        .collect::<BTreeSet<_>>();
+    let mut ordered = Vec::with_capacity(pages.len());
+    let mut ambiguous = None;
+    while let Some(i) = ready.pop_first() {
+        // copies of one page are interchangeable, so only a choice between
+        // different pages counts
+        if ambiguous.is_none() && ready.iter().any(|&j| pages[j] != pages[i]) {
+            let choices = std::iter::once(i).chain(ready.iter().copied());
+            ambiguous = Some(choices.map(|i| pages[i]).unique().collect());
+        }
+        ordered.push(pages[i]);
+        for &j in &after[i] {
+            before_count[j] -= 1;
+            if before_count[j] == 0 {
+                ready.insert(j);
+            }
+        }
+    }
+
+    if ordered.len() < pages.len() {
+        return Err(cycle(pages, &after, &before_count));
+    }
+    Ok(Ordered {
+        pages: ordered,
+        ambiguous,
+    })
+}
+
+// a cycle among the pages Kahn's algorithm couldn't place. Each of those
+// still has an unplaced page before it, so walking backwards from any of
+// them must eventually come round to a page already visited.
+fn cycle(pages: &[u32], after: &[Vec<usize>], before_count: &[usize]) -> Cycle {
+    let stuck = |i: usize| before_count[i] > 0;
+    let mut before = vec![None; pages.len()];
+    for i in (0..pages.len()).filter(|&i| stuck(i)) {
+        for &j in after[i].iter().filter(|&&j| stuck(j)) {
+            before[j] = Some(i);
+        }
+    }
+
+    let mut walked = Vec::new();
+    let mut i = (0..pages.len())
+        .find(|&i| stuck(i))
+        .expect("some page is stuck");
+    while !walked.contains(&i) {
+        walked.push(i);
+        i = before[i].expect("stuck pages have a stuck page before them");
+    }
+    let start = walked.iter().position(|&j| j == i).unwrap();
+    let mut cycle = walked[start..]
+        .iter()
+        .rev()
+        .map(|&i| pages[i])
+        .collect_vec();
+
+    // every copy of a page has the same rules, so if the cycle passes
+    // through a page twice, the part in between is a cycle on its own
+    for end in 1..cycle.len() {
+        if let Some(start) = cycle[..end].iter().position(|&p| p == cycle[end]) {
+            cycle = cycle[start..end].to_vec();
+            break;
+        }
+    }
+    Cycle(cycle)
+}
+
+fn parse(input: &str) -> Result<(Vec<Rule>, Vec<Vec<u32>>), ParseError> {
+    finish(
+        input,
+        separated_pair(
+            separated_list1(newline, rule),
+            count(newline, 2),
+            separated_list1(newline, list),
+        )(input),
+    )
+}
+
+fn part1(rules: &[Rule], lists: &[Vec<u32>]) -> u32 {
+    let direct_cmp = |x, y| {
+        if rules.contains(&(x, y)) {
+            return Some(Ordering::Less);
+        }
+        if rules.contains(&(y, x)) {
+            return Some(Ordering::Greater);
+        }
+        None
+    };
+
+    lists
+        .iter()
+        .filter(|list| {
+            !(0..list.len())
+                .flat_map(|i| (i..list.len()).map(move |j| direct_cmp(list[i], list[j])))
+                .any(|cmp| cmp == Some(Ordering::Greater))
+        })
+        .map(|list| list[list.len() / 2])
+        .sum()
+}
+
+fn part2(rules: &[Rule], lists: &[Vec<u32>]) -> u32 {
+    lists
+        .iter()
+        .filter_map(|list| {
+            // an update that can't be ordered has no middle page to add
+            let ordered = match order(rules, list) {
+                Ok(ordered) => ordered,
+                Err(cycle) => {
+                    diagnostics::note(|| json!({ "update": list, "cycle": cycle.0 }));
+                    return None;
+                }
+            };
+            if let Some(pages) = &ordered.ambiguous {
+                diagnostics::note(
+                    || json!({ "update": list, "order": ordered.pages, "ambiguous": pages }),
+                );
+            }
+            (ordered.pages != *list).then(|| ordered.pages[ordered.pages.len() / 2])
+        })
+        .sum()
+}
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+
+    type Input = (Vec<Rule>, Vec<Vec<u32>>);
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1((rules, lists): &Self::Input) -> u32 {
+        part1(rules, lists)
+    }
+
+    fn part2((rules, lists): &Self::Input) -> u32 {
+        part2(rules, lists)
+    }
+}
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+    use super::*;
+    use crate::generate::{self, Rng};
+
+    #[test]
+    fn test_part1() {
+        let (r, l) = parse(INPUT).unwrap();
+        assert_eq!(part1(&r, &l), 143);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse("47|53\n97|x\n\n75,47").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(parse("47|53\n\n75,47\n").unwrap().1, [[75, 47]]);
+
+        let error = parse("47|53\n\n75,47\n75,x").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 4));
+    }
+
+    #[test]
+    fn test_part2() {
+        let (r, l) = parse(INPUT).unwrap();
+        assert_eq!(part2(&r, &l), 123);
+    }
+
+    #[test]
+    fn test_order() {
+        let (rules, _) = parse(INPUT).unwrap();
+        let ordered = order(&rules, &[97, 13, 75, 29, 47]).unwrap();
+        assert_eq!(ordered.pages, [97, 75, 47, 29, 13]);
+        assert_eq!(ordered.ambiguous, None);
+        assert_eq!(order(&rules, &[75, 47, 61]).unwrap().pages, [75, 47, 61]);
+
+        // no rule between 2 and 4, but the chain still fixes their order
+        let chain = [(1, 2), (2, 3), (3, 4), (4, 5)];
+        let ordered = order(&chain, &[4, 2, 5, 1, 3]).unwrap();
+        assert_eq!(ordered.pages, [1, 2, 3, 4, 5]);
+        assert_eq!(ordered.ambiguous, None);
+
+        let ordered = order(&[(1, 2), (1, 3)], &[3, 2, 1]).unwrap();
+        assert_eq!(ordered.pages, [1, 3, 2]);
+        assert_eq!(ordered.ambiguous, Some(vec![3, 2]));
+        let ordered = order(&[(1, 2)], &[5, 2, 1]).unwrap();
+        assert_eq!(ordered.ambiguous, Some(vec![5, 1]));
+
+        let cycle = order(&[(1, 2), (2, 3), (3, 1), (3, 4)], &[4, 3, 2, 1]).unwrap_err();
+        assert_eq!(cycle, Cycle(vec![1, 2, 3]));
+        assert_eq!(
+            cycle.to_string(),
+            "pages must come before themselves: 1 -> 2 -> 3 -> 1"
+        );
+        assert_eq!(order(&[(7, 7)], &[7]), Err(Cycle(vec![7])));
+    }
+
+    #[test]
+    fn test_order_repeats() {
+        let ordered = order(&[(1, 2), (2, 3)], &[3, 2, 3, 1, 2]).unwrap();
+        assert_eq!(ordered.pages, [1, 2, 2, 3, 3]);
+        assert_eq!(ordered.ambiguous, None);
+        let ordered = order(&[(1, 2)], &[2, 5, 1, 2]).unwrap();
+        assert_eq!(ordered.pages, [5, 1, 2, 2]);
+        assert_eq!(ordered.ambiguous, Some(vec![5, 1]));
+
+        let cycle = order(&[(1, 2), (2, 1)], &[1, 2, 1, 2]).unwrap_err();
+        assert_eq!(cycle.0.iter().sorted().collect_vec(), [&1, &2]);
+    }
+
+    #[test]
+    fn test_part2_cycle() {
+        // part 1 doesn't need an order, so a cycle in one update only
+        // leaves that update out of part 2
+        let (rules, lists) = parse("1|2\n2|3\n3|1\n\n1,2\n4,2,3,1,5\n2,1").unwrap();
+        assert_eq!(part1(&rules, &lists), 2);
+        let (fixed, notes) = diagnostics::collect(|| part2(&rules, &lists));
+        assert_eq!(fixed, 2);
+        assert_eq!(
+            notes,
+            [json!({ "update": [4, 2, 3, 1, 5], "cycle": [3, 1, 2] })]
+        );
+    }
+
+    #[test]
+    fn test_part2_ambiguous() {
+        let (rules, lists) = parse("1|2\n1|3\n\n2,1,3\n1,2,3").unwrap();
+        let (fixed, notes) = diagnostics::collect(|| part2(&rules, &lists));
+        assert_eq!(fixed, 2);
+        assert_eq!(
+            notes,
+            [
+                json!({ "update": [2, 1, 3], "order": [1, 2, 3], "ambiguous": [2, 3] }),
+                json!({ "update": [1, 2, 3], "order": [1, 2, 3], "ambiguous": [2, 3] }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_oracle() {
+        for seed in 0..200 {
+            let input = generate::day5(&mut Rng::new(seed));
+            let (rules, lists) = parse(&input).unwrap();
+            // the rules are a total order, so a page's place in it is how
+            // many pages must come after it
+            let rank = |page| std::cmp::Reverse(rules.iter().filter(|(a, _)| *a == page).count());
+            let (mut correct, mut fixed) = (0, 0);
+            for list in &lists {
+                let mut sorted = list.clone();
+                sorted.sort_by_key(|&page| rank(page));
+                if &sorted == list {
+                    correct += list[list.len() / 2];
+                } else {
+                    fixed += sorted[sorted.len() / 2];
+                }
+            }
+            assert_eq!(part1(&rules, &lists), correct, "seed {seed}");
+            assert_eq!(part2(&rules, &lists), fixed, "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{
+    grid::{Direction, Grid, Point},
+    ParseError, Solution,
+};
+
+pub struct Day6;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Cell {
+    Empty,
+    Obstacle,
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cell::Obstacle => write!(f, "#"),
+            Cell::Empty => write!(f, "."),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Guard {
+    position: Point,
+    heading: Direction,
+}
+
+impl Guard {
+    fn next_step(&self) -> Point {
+        self.position + self.heading
+    }
+
+    fn step(&mut self) {
+        self.position = self.next_step()
+    }
+
+    fn turn(&mut self) {
+        self.heading = self.heading.turn_right()
+    }
+}
+
+fn parse(input: &str) -> Result<(Grid<Cell>, Guard), ParseError> {
+    let grid = Grid::parse(input, |c| matches!(c, '.' | '#' | '^').then_some(c))?;
+    let position = grid
+        .enumerate()
+        .find_map(|(point, &c)| (c == '^').then_some(point))
+        .ok_or_else(|| ParseError::new(input, 0, 0, "no guard `^` on the map"))?;
+
+    Ok((
+        grid.map(|&c| match c {
+            '#' => Cell::Obstacle,
+            _ => Cell::Empty,
+        }),
+        Guard {
+            position,
+            heading: Direction::N,
+        },
+    ))
+}
+
+fn visits(grid: &Grid<Cell>, mut guard: Guard) -> HashSet<Point> {
+    let mut visited = HashSet::with_capacity(grid.len());
+    loop {
+        visited.insert(guard.position);
+        match grid.get(guard.next_step()) {
+            Some(Cell::Empty) => guard.step(),
+            Some(Cell::Obstacle) => guard.turn(),
+            None => break,
+        }
+    }
+    visited
+}
+fn part1(grid: &Grid<Cell>, guard: Guard) -> u32 {
+    visits(grid, guard).len() as u32
+}
+
+fn loops(grid: &Grid<Cell>, mut guard: Guard) -> bool {
+    let mut visited = HashSet::with_capacity(grid.len());
+    loop {
+        if visited.contains(&guard) {
+            return true;
+        }
+        visited.insert(guard);
+        match grid.get(guard.next_step()) {
+            Some(Cell::Empty) => guard.step(),
+            Some(Cell::Obstacle) => guard.turn(),
+            None => return false,
+        }
+    }
+}
+
+fn part2(mut grid: Grid<Cell>, guard: Guard) -> u32 {
+    let mut n_loops = 0;
+    let mut visits = visits(&grid, guard);
+    visits.remove(&guard.position);
+    for point in visits {
+        let is_empty = matches!(grid.get(point), Some(Cell::Empty));
+        if is_empty {
+            grid.set(point, Cell::Obstacle);
+            if loops(&grid, guard) {
+                n_loops += 1
+            }
+            grid.set(point, Cell::Empty);
+        }
+    }
+    n_loops
+}
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+
+    type Input = (Grid<Cell>, Guard);
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1((grid, guard): &Self::Input) -> u32 {
+        part1(grid, *guard)
+    }
+
+    fn part2((grid, guard): &Self::Input) -> u32 {
+        part2(grid.clone(), *guard)
+    }
+}
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    use super::*;
+    use crate::generate::{self, Rng};
+
+    #[test]
+    fn test_part1() {
+        let (grid, guard) = parse(INPUT).unwrap();
+        assert_eq!(part1(&grid, guard), 41);
+    }
+
+    #[test]
+    fn test_part2() {
+        let (grid, guard) = parse(INPUT).unwrap();
+        assert_eq!(part2(grid, guard), 6);
+    }
+
+    #[test]
+    fn test_part2_oracle() {
+        for seed in 0..100 {
+            let input = generate::day6(&mut Rng::new(seed));
+            let (grid, guard) = parse(&input).unwrap();
+            // try an obstacle on every empty cell, not just on the guard's path
+            let oracle = grid
+                .points()
+                .filter(|&point| grid[point] == Cell::Empty && point != guard.position)
+                .filter(|&point| {
+                    let mut grid = grid.clone();
+                    grid.set(point, Cell::Obstacle);
+                    loops(&grid, guard)
+                })
+                .count() as u32;
+            assert_eq!(part2(grid, guard), oracle, "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::ops::Rem;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::newline,
+    combinator::{cut, map_res},
+    multi::separated_list1,
+    sequence::separated_pair,
+    IResult,
+};
+
+use serde_json::json;
+
+use crate::{diagnostics, parse::finish, ParseError, Solution};
+
+pub struct Day7;
+
+fn digit1(input: &str) -> IResult<&str, u64> {
+    map_res(nom::character::complete::digit1, str::parse::<u64>)(input)
+}
+
+fn rhs(input: &str) -> IResult<&str, Vec<u64>> {
+    separated_list1(tag(" "), cut(digit1))(input)
+}
+fn equation(input: &str) -> IResult<&str, (u64, Vec<u64>)> {
+    separated_pair(digit1, tag(": "), rhs)(input)
+}
+fn parse(input: &str) -> Result<Vec<(u64, Vec<u64>)>, ParseError> {
+    finish(input, separated_list1(newline, equation)(input))
+}
+
+//assumes the components are reversed
+fn can_be_made(target: u64, components: &[u64]) -> bool {
+    let Some((&last, rest)) = components.split_first() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return target == last;
+    }
+    let by_addition = target >= last && can_be_made(target - last, rest);
+
+    // anything times 0 is 0, whatever the rest make
+    let by_multiplication = match last {
+        0 => target == 0,
+        _ => target.rem(last) == 0 && can_be_made(target / last, rest),
+    };
+
+    by_addition || by_multiplication
+}
+
+//assumes the components are reversed
+fn can_be_made_p2(target: u64, components: &[u64]) -> bool {
+    let Some((&last, rest)) = components.split_first() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return target == last;
+    }
+
+    if target >= last && can_be_made_p2(target - last, rest) {
+        return true;
+    }
+
+    let by_multiplication = match last {
+        0 => target == 0,
+        _ => target.rem(last) == 0 && can_be_made_p2(target / last, rest),
+    };
+    if by_multiplication {
+        return true;
+    }
+
+    // nothing left after the suffix means the rest made 0, as `05` is 5
+    if let Some(new_target) = target.to_string().strip_suffix(&last.to_string()) {
+        return can_be_made_p2(new_target.parse::<u64>().unwrap_or_default(), rest);
+    };
+
+    false
+}
+
+// `components` are reversed, as for `can_be_made`
+fn note(target: u64, components: &[u64], solvable: bool) {
+    diagnostics::note(|| {
+        let numbers = components.iter().rev().collect::<Vec<_>>();
+        json!({ "target": target, "numbers": numbers, "solvable": solvable })
+    });
+}
+
+fn part1(input: Vec<(u64, Vec<u64>)>) -> u64 {
+    let mut result = 0;
+    for (target, mut components) in input {
+        components.reverse();
+        let solvable = can_be_made(target, &components);
+        note(target, &components, solvable);
+        if solvable {
+            result += target
+        }
+    }
+    result
+}
+
+fn part2(input: Vec<(u64, Vec<u64>)>) -> u64 {
+    let mut result = 0;
+    for (target, mut components) in input {
+        components.reverse();
+        let solvable = can_be_made_p2(target, &components);
+        note(target, &components, solvable);
+        if solvable {
+            result += target
+        }
+    }
+    result
+}
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+
+    type Input = Vec<(u64, Vec<u64>)>;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        part1(input.clone())
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input.clone())
+    }
+}
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
+    use super::*;
+    use crate::generate::{self, Rng};
+    use itertools::Itertools;
+
+    #[test]
+    fn test_part1() {
+        let input = parse(INPUT).unwrap();
+        assert_eq!(part1(input), 3749);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse("190: 10 19\n3267: 81 x 27\n").unwrap_err();
+        assert_eq!(error.message, "unexpected `x`");
+        assert_eq!((error.line, error.column), (2, 10));
+
+        let error = parse("190: 10 19\n\n3267: 81 40 27\n").unwrap_err();
+        assert_eq!(error.message, "unexpected blank line");
+    }
+
+    #[test]
+    fn test_zeros() {
+        let input = parse("5: 0 5\n7: 7 0\n70: 7 0\n6: 0 6 0\n").unwrap();
+        assert_eq!(part1(input.clone()), 5 + 7 + 6);
+        assert_eq!(part2(input), 5 + 7 + 70 + 6);
+
+        // reversed, so `0: 0 5` is made as 0 * 5, but `0: 5` is just 5
+        assert!(can_be_made(0, &[5, 0]));
+        assert!(!can_be_made(0, &[5]));
+        assert!(can_be_made_p2(5, &[5, 0]));
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = parse(INPUT).unwrap();
+        assert_eq!(part2(input), 11387);
+    }
+
+    // tries every sequence of `operators`, evaluating left to right
+    fn solvable(target: u64, numbers: &[u64], operators: &[fn(u64, u64) -> u64]) -> bool {
+        (1..numbers.len())
+            .map(|_| operators)
+            .multi_cartesian_product()
+            .any(|ops| {
+                numbers[1..]
+                    .iter()
+                    .zip(ops)
+                    .fold(numbers[0], |acc, (&n, op)| op(acc, n))
+                    == target
+            })
+            || (numbers.len() == 1 && numbers[0] == target)
+    }
+
+    #[test]
+    fn test_oracle() {
+        let add = |a, b| a + b;
+        let mul = |a, b| a * b;
+        let concat = |a, b| format!("{a}{b}").parse().unwrap();
+        for seed in 0..200 {
+            let input = parse(&generate::day7(&mut Rng::new(seed))).unwrap();
+            for (target, numbers) in input {
+                let mut reversed = numbers.clone();
+                reversed.reverse();
+                assert_eq!(
+                    can_be_made(target, &reversed),
+                    solvable(target, &numbers, &[add, mul]),
+                    "seed {seed}: {target}: {numbers:?}"
+                );
+                assert_eq!(
+                    can_be_made_p2(target, &reversed),
+                    solvable(target, &numbers, &[add, mul, concat]),
+                    "seed {seed}: {target}: {numbers:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Rem,
+};
+
+use crate::{grid::Grid, ParseError, Solution};
+
+pub struct Day8;
+
+#[derive(Debug)]
+pub struct Antennaes {
+    width: usize,
+    height: usize,
+    inner: HashMap<char, Vec<(usize, usize)>>,
+}
+fn parse(input: &str) -> Result<Antennaes, ParseError> {
+    let grid = Grid::parse(input, Some)?;
+    if grid.is_empty() {
+        return Err(ParseError::new(input, 0, 0, "empty map"));
+    }
+    let mut inner = HashMap::new();
+    for (point, &char) in grid.enumerate() {
+        if char != '.' {
+            inner
+                .entry(char)
+                .or_insert(Vec::new())
+                .push((point.x as usize, point.y as usize))
+        }
+    }
+    Ok(Antennaes {
+        width: grid.width(),
+        height: grid.height(),
+        inner,
+    })
+}
+fn part1(antennaes: &Antennaes) -> usize {
+    let mut antinodes = HashSet::new();
+    for (_, positions) in antennaes.inner.iter() {
+        for &(x, y) in positions {
+            let range = (
+                x.min(antennaes.width - x - 1),
+                y.min(antennaes.height - y - 1),
+            );
+
+            for (ox, oy) in positions.iter().filter(|&&(ox, oy)| {
+                !(x == ox && y == oy)
+                    && ox <= x + range.0
+                    && ox >= x - range.0
+                    && oy <= y + range.1
+                    && oy >= y - range.1
+            }) {
+                antinodes.insert((2 * x - ox, 2 * y - oy));
+            }
+        }
+    }
+    antinodes.len()
+}
+
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn part2(antennaes: &Antennaes) -> usize {
+    let mut antinodes = HashSet::new();
+    let explore_iter = (1isize..)
+        .map(|x| if x.rem(2) == 0 { x / 2 } else { -x / 2 })
+        .take(antennaes.width.max(antennaes.height) * 2);
+
+    for (_, positions) in antennaes.inner.iter() {
+        for &(x, y) in positions {
+            for (ox, oy) in positions.iter().filter(|&&(ox, oy)| (ox, oy) > (x, y)) {
+                let (x, y, ox, oy) = (x as isize, y as isize, *ox as isize, *oy as isize);
+                // step by the smallest offset in line with both antennas, so
+                // e.g. (2, 2) apart also finds the cell between them
+                let step = gcd(ox - x, oy - y);
+                let diff = ((ox - x) / step, (oy - y) / step);
+                for multiple in explore_iter.clone() {
+                    let (cx, cy) = (x + diff.0 * multiple, y + diff.1 * multiple);
+                    if cx < 0
+                        || cx >= antennaes.width as isize
+                        || cy < 0
+                        || cy >= antennaes.height as isize
+                    {
+                        continue;
+                    }
+                    antinodes.insert((cx, cy));
+                }
+            }
+        }
+    }
+    antinodes.len()
+}
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+
+    type Input = Antennaes;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        part2(input)
+    }
+}
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+
+    use super::*;
+    use crate::generate::{self, Rng};
+    use itertools::Itertools;
+
+    #[test]
+    fn test_part1() {
+        let input = parse(INPUT).unwrap();
+        assert_eq!(part1(&input), 14);
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = parse(INPUT).unwrap();
+        assert_eq!(part2(&input), 34);
+    }
+
+    #[test]
+    fn test_part2_between() {
+        // (2, 2) apart, so the cell halfway between is in line with both;
+        // stepping by the whole offset used to skip it
+        let input = parse("a....\n.....\n..a..\n.....\n.....\n").unwrap();
+        assert_eq!(part2(&input), 5);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse("..a.\n..\n").unwrap_err();
+        assert_eq!(error.message, "expected 4 columns, found 2");
+        assert_eq!((error.line, error.column), (2, 3));
+        assert!(parse("..é.\n....\n").is_ok());
+        assert_eq!(parse("").unwrap_err().message, "empty map");
+    }
+
+    #[test]
+    fn test_oracle() {
+        for seed in 0..200 {
+            let input = generate::day8(&mut Rng::new(seed));
+            let antennaes = parse(&input).unwrap();
+            let pairs = antennaes
+                .inner
+                .values()
+                .flat_map(|positions| positions.iter().tuple_combinations())
+                .map(|(&(ax, ay), &(bx, by))| {
+                    ((ax as isize, ay as isize), (bx as isize, by as isize))
+                })
+                .collect::<Vec<_>>();
+            let cells = (0..antennaes.height as isize)
+                .flat_map(|y| (0..antennaes.width as isize).map(move |x| (x, y)));
+
+            // in line with both antennas, and twice as far from one as the other
+            let doubled = cells
+                .clone()
+                .filter(|&(x, y)| {
+                    pairs.iter().any(|&((ax, ay), (bx, by))| {
+                        (x - ax, y - ay) == (2 * (x - bx), 2 * (y - by))
+                            || (x - bx, y - by) == (2 * (x - ax), 2 * (y - ay))
+                    })
+                })
+                .count();
+            let in_line = cells
+                .filter(|&(x, y)| {
+                    pairs
+                        .iter()
+                        .any(|&((ax, ay), (bx, by))| (x - ax) * (by - ay) == (y - ay) * (bx - ax))
+                })
+                .count();
+
+            assert_eq!(part1(&antennaes), doubled, "seed {seed}");
+            assert_eq!(part2(&antennaes), in_line, "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{ParseError, Solution};
+
+pub struct Day9;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct FileOrSpace {
+    id: Option<usize>,
+    length: usize,
+}
+
+impl FileOrSpace {
+    fn is_file(&self) -> bool {
+        self.id.is_some()
+    }
+
+    fn is_space(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+// sum of n numbers from "from" of length "length"
+// e.g triangle_run(0, 5) = 0 + 1 + 2 + 3 + 4
+fn triangle_run(from: usize, length: usize) -> usize {
+    let to = from + length;
+    if length == 0 {
+        return 0;
+    }
+    if from == 0 {
+        return to * (to - 1) / 2;
+    }
+    ((to * (to - 1)) - (from * (from - 1))) / 2
+}
+fn parse(input: &str) -> Result<Vec<FileOrSpace>, ParseError> {
+    let disk_map = input.strip_suffix('\n').unwrap_or(input);
+    if disk_map.is_empty() {
+        return Err(ParseError::new(input, 0, 0, "empty disk map"));
+    }
+    disk_map
+        .char_indices()
+        .enumerate()
+        .map(|(i, (offset, x))| {
+            let length = x.to_digit(10).ok_or_else(|| {
+                ParseError::new(
+                    input,
+                    offset,
+                    x.len_utf8(),
+                    format!("expected a digit, found `{}`", x.escape_default()),
+                )
+            })? as usize;
+            Ok(match i % 2 {
+                0 => FileOrSpace {
+                    id: Some(i / 2),
+                    length,
+                },
+                1 => FileOrSpace { id: None, length },
+                _ => unreachable!(),
+            })
+        })
+        .collect()
+}
+
+fn calculate_checksum(files: &[FileOrSpace]) -> usize {
+    files
+        .iter()
+        .fold((0, 0), |(len, total), file| {
+            if file.is_file() {
+                (
+                    len + file.length,
+                    (total + triangle_run(len, file.length) * file.id.unwrap()),
+                )
+            } else {
+                (len + file.length, total)
+            }
+        })
+        .1
+}
+fn part1(mut files: Vec<FileOrSpace>) -> usize {
+    // runs out only if every file was empty
+    'outer: while let Some(mut file) = files.pop() {
+        if file.is_space() {
+            continue;
+        }
+        'inner: while file.length > 0 {
+            let i = files.iter().position(|x| x.is_space());
+            if let Some(i) = i {
+                let length = files[i].length;
+                if length <= file.length {
+                    files[i].id = file.id;
+                    file.length -= length;
+                } else {
+                    files[i].length -= file.length;
+                    files.insert(i, file);
+                    break 'inner;
+                }
+            } else {
+                files.push(file);
+                break 'outer;
+            }
+        }
+    }
+    calculate_checksum(&files)
+}
+
+fn part2(mut files: Vec<FileOrSpace>) -> usize {
+    let mut moving_id = files[files.len() - 1]
+        .id
+        .unwrap_or_else(|| files[files.len() - 2].id.unwrap());
+
+    while moving_id > 0 {
+        let file_index = files
+            .iter()
+            .position(|file| file.id == Some(moving_id))
+            .unwrap();
+
+        let file = files[file_index];
+
+        let space_index = files[..file_index]
+            .iter()
+            .position(|x| x.is_space() && x.length >= file.length);
+
+        if let Some(space_index) = space_index {
+            files[space_index].length -= file.length;
+            files[file_index].id = None;
+            files.insert(space_index, file);
+        }
+        moving_id -= 1;
+    }
+    calculate_checksum(&files)
+}
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+
+    type Input = Vec<FileOrSpace>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        part1(input.clone())
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        part2(input.clone())
+    }
+}
+#[cfg(test)]
+mod test {
+    const INPUT: &str = "2333133121414131402";
+
+    use super::*;
+    use crate::generate::{self, Rng};
+
+    #[test]
+    fn test_triangle_run() {
+        assert_eq!(triangle_run(0, 5), 10);
+        assert_eq!(triangle_run(1, 4), 10);
+        assert_eq!(triangle_run(2, 3), 9);
+        assert_eq!(triangle_run(3, 2), 7);
+    }
+    #[test]
+    fn test_part1() {
+        let input = parse(INPUT).unwrap();
+        assert_eq!(part1(input), 1928);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("12\n").unwrap().len(), 2);
+        let error = parse("12\n\n").unwrap_err();
+        assert_eq!(error.message, "expected a digit, found `\\n`");
+        assert_eq!((error.line, error.column), (1, 3));
+        assert_eq!(parse("\n").unwrap_err().message, "empty disk map");
+        assert_eq!(parse("").unwrap_err().message, "empty disk map");
+    }
+
+    #[test]
+    fn test_empty_files() {
+        assert_eq!(part1(parse("0\n").unwrap()), 0);
+        assert_eq!(part2(parse("0\n").unwrap()), 0);
+        assert_eq!(part1(parse("103").unwrap()), 1 + 2 + 3);
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = parse(INPUT).unwrap();
+        assert_eq!(part2(input), 2858);
+    }
+
+    // lays the disk out block by block
+    fn blocks(files: &[FileOrSpace]) -> Vec<Option<usize>> {
+        files
+            .iter()
+            .flat_map(|file| std::iter::repeat_n(file.id, file.length))
+            .collect()
+    }
+
+    fn checksum(blocks: &[Option<usize>]) -> usize {
+        blocks
+            .iter()
+            .enumerate()
+            .map(|(i, id)| i * id.unwrap_or(0))
+            .sum()
+    }
+
+    #[test]
+    fn test_oracle() {
+        for seed in 0..200 {
+            let files = parse(&generate::day9(&mut Rng::new(seed))).unwrap();
+
+            // move the last block into the first gap until there are no gaps
+            let mut disk = blocks(&files);
+            while let Some(gap) = disk.iter().position(Option::is_none) {
+                match disk.pop().unwrap() {
+                    Some(id) if gap < disk.len() => disk[gap] = Some(id),
+                    Some(id) => disk.push(Some(id)),
+                    None => continue,
+                }
+                if gap >= disk.len() {
+                    break;
+                }
+            }
+            assert_eq!(part1(files.clone()), checksum(&disk), "seed {seed}");
+
+            // move each whole file, highest id first, into the first gap to
+            // its left that fits it
+            let mut disk = blocks(&files);
+            let last_id = files.iter().filter_map(|file| file.id).max().unwrap();
+            for id in (0..=last_id).rev() {
+                let start = disk.iter().position(|&b| b == Some(id)).unwrap();
+                let len = disk.iter().filter(|&&b| b == Some(id)).count();
+                let gap = (0..start).find(|&i| disk[i..i + len].iter().all(Option::is_none));
+                if let Some(gap) = gap {
+                    disk[start..start + len].fill(None);
+                    disk[gap..gap + len].fill(Some(id));
+                }
+            }
+            assert_eq!(part2(files), checksum(&disk), "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,32 @@
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+pub use day1::Day1;
+pub use day10::Day10;
+pub use day2::Day2;
+pub use day3::Day3;
+pub use day4::Day4;
+pub use day5::Day5;
+pub use day6::Day6;
+pub use day7::Day7;
+pub use day8::Day8;
+pub use day9::Day9;
+
+use crate::Runner;
+
+/// Every solved day, in order.
+pub static DAYS: &[&dyn Runner] = &[
+    &Day1, &Day2, &Day3, &Day4, &Day5, &Day6, &Day7, &Day8, &Day9, &Day10,
+];
+
+pub fn get(day: u32) -> Option<&'static dyn Runner> {
+    DAYS.iter().copied().find(|runner| runner.day() == day)
+}
@@ -0,0 +1,59 @@
+//! Per-item notes from inside a part, e.g. which day 7 equations could be
+//! solved.
+//!
+//! Parts call [`note`] rather than printing, so nothing they say ends up
+//! mixed in with the answers. Notes are only built while a [`collect`] is
+//! running on the same thread; otherwise `note` does nothing.
+
+use std::cell::RefCell;
+
+use serde_json::Value;
+
+thread_local! {
+    static NOTES: RefCell<Option<Vec<Value>>> = const { RefCell::new(None) };
+}
+
+/// Records a note, if anyone is collecting them. `f` is only called when
+/// they are.
+pub fn note(f: impl FnOnce() -> Value) {
+    NOTES.with(|notes| {
+        if let Some(notes) = notes.borrow_mut().as_mut() {
+            notes.push(f());
+        }
+    })
+}
+
+/// Runs `f`, returning its result along with every note it made.
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<Value>) {
+    let outer = NOTES.with(|notes| notes.borrow_mut().replace(Vec::new()));
+    let out = f();
+    let collected = NOTES.with(|notes| std::mem::replace(&mut *notes.borrow_mut(), outer));
+    (out, collected.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let mut calls = 0;
+        note(|| {
+            calls += 1;
+            json!(0)
+        });
+        assert_eq!(calls, 0);
+
+        let (out, notes) = collect(|| {
+            note(|| json!(1));
+            let ((), inner) = collect(|| note(|| json!(2)));
+            assert_eq!(inner, [json!(2)]);
+            note(|| json!(3));
+            "done"
+        });
+        assert_eq!(out, "done");
+        assert_eq!(notes, [json!(1), json!(3)]);
+    }
+}
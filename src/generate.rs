@@ -0,0 +1,290 @@
+//! Random puzzle inputs in each day's format, for property tests.
+//!
+//! Everything is driven by a seeded [`Rng`], so a failing case can be
+//! reproduced from its seed alone. Inputs are kept small enough that
+//! brute-force checks over them stay quick.
+
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+/// A small seedable generator (splitmix64). Not for anything that needs real
+/// randomness, just repeatable test data.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `lo..=hi`
+    pub fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next_u64() % (hi - lo + 1)
+    }
+
+    /// True with probability `percent`/100
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(1, 100) <= percent
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.range(0, items.len() as u64 - 1) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0, i as u64) as usize);
+        }
+    }
+}
+
+/// A random input for `day`, or `None` for days without a generator.
+pub fn input(day: u32, rng: &mut Rng) -> Option<String> {
+    Some(match day {
+        1 => day1(rng),
+        2 => day2(rng),
+        3 => day3(rng),
+        4 => day4(rng),
+        5 => day5(rng),
+        6 => day6(rng),
+        7 => day7(rng),
+        8 => day8(rng),
+        9 => day9(rng),
+        10 => day10(rng),
+        _ => return None,
+    })
+}
+
+// a `width` by `height` grid, one character per cell
+fn grid(width: u64, height: u64, mut cell: impl FnMut(u64, u64) -> char) -> String {
+    (0..height)
+        .map(|y| (0..width).map(|x| cell(x, y)).collect::<String>() + "\n")
+        .collect()
+}
+
+/// Two columns of numbers, with plenty of repeats between them.
+pub fn day1(rng: &mut Rng) -> String {
+    let max = rng.range(1, 50);
+    (0..rng.range(1, 8))
+        .map(|_| format!("{}   {}\n", rng.range(0, max), rng.range(0, max)))
+        .collect()
+}
+
+/// Reports of 2 to 8 levels, mostly small steps in one direction so that a
+/// good share of them are safe or nearly so.
+pub fn day2(rng: &mut Rng) -> String {
+    (0..rng.range(1, 10))
+        .map(|_| {
+            let mut level = rng.range(10, 90) as i64;
+            let sign = if rng.chance(50) { 1 } else { -1 };
+            (0..rng.range(2, 8))
+                .map(|_| {
+                    let current = level;
+                    let step = if rng.chance(80) {
+                        sign * rng.range(1, 3) as i64
+                    } else {
+                        rng.range(0, 10) as i64 - 5
+                    };
+                    level = (level + step).max(1);
+                    current
+                })
+                .join(" ")
+                + "\n"
+        })
+        .collect()
+}
+
+/// Corrupted memory: `mul`, `do` and `don't` instructions, whole or broken,
+/// among random junk.
+pub fn day3(rng: &mut Rng) -> String {
+    let mut memory = String::new();
+    for _ in 0..rng.range(0, 20) {
+        match rng.range(0, 5) {
+            0 | 1 => write!(memory, "mul({},{})", rng.range(0, 999), rng.range(0, 999)).unwrap(),
+            2 => memory.push_str(rng.pick(&["do()", "don't()"])),
+            3 => memory.push_str(rng.pick(&[
+                "mul(",
+                "mul[1,2]",
+                "mul(1, 2)",
+                "mul(1234,5)",
+                "do(",
+                "don't",
+                "mul(4,",
+                ")",
+            ])),
+            _ => memory.push(rng.pick(&['x', '%', '(', ')', ',', '1', 'm', 'u', 'l', ' '])),
+        }
+    }
+    memory + "\n"
+}
+
+/// A word search made only of the letters in XMAS, so that matches are
+/// common.
+pub fn day4(rng: &mut Rng) -> String {
+    grid(rng.range(1, 12), rng.range(1, 12), |_, _| {
+        rng.pick(&['X', 'M', 'A', 'S'])
+    })
+}
+
+/// Ordering rules and updates. The rules give a total order on the pages,
+/// so every pair of pages in an update can be compared, as in the real
+/// input. Updates have an odd number of distinct pages.
+pub fn day5(rng: &mut Rng) -> String {
+    let mut pages = (10..rng.range(13, 40)).collect_vec();
+    rng.shuffle(&mut pages);
+
+    let mut rules = pages
+        .iter()
+        .tuple_combinations()
+        .map(|(a, b)| format!("{a}|{b}"))
+        .collect_vec();
+    rng.shuffle(&mut rules);
+
+    let updates = (0..rng.range(1, 8))
+        .map(|_| {
+            let mut update = pages.clone();
+            rng.shuffle(&mut update);
+            let len = rng.range(0, (pages.len() as u64 - 1) / 2) * 2 + 1;
+            update.truncate(len as usize);
+            if rng.chance(50) {
+                update.sort_by_key(|page| pages.iter().position(|p| p == page));
+            }
+            update.iter().join(",")
+        })
+        .collect_vec();
+
+    format!("{}\n\n{}\n", rules.join("\n"), updates.join("\n"))
+}
+
+/// A map with scattered obstacles and exactly one guard, who eventually walks
+/// off it (as in every real input).
+pub fn day6(rng: &mut Rng) -> String {
+    loop {
+        let (width, height) = (rng.range(1, 12), rng.range(1, 12));
+        let guard = (rng.range(0, width - 1), rng.range(0, height - 1));
+        let density = rng.range(5, 30);
+        let map = grid(width, height, |x, y| match (x, y) == guard {
+            true => '^',
+            false if rng.chance(density) => '#',
+            false => '.',
+        });
+        if guard_escapes(&map) {
+            return map;
+        }
+    }
+}
+
+fn guard_escapes(map: &str) -> bool {
+    let rows = map.lines().map(str::as_bytes).collect_vec();
+    let cell = |x: isize, y: isize| rows.get(y as usize)?.get(x as usize).copied();
+    let (mut x, mut y) = rows
+        .iter()
+        .enumerate()
+        .find_map(|(y, row)| Some((row.iter().position(|&c| c == b'^')? as isize, y as isize)))
+        .unwrap();
+    let (mut dx, mut dy) = (0, -1);
+    // there are only so many states, so any walk longer than that is a loop
+    for _ in 0..=4 * map.len() {
+        match cell(x + dx, y + dy) {
+            None => return true,
+            Some(b'#') => (dx, dy) = (-dy, dx),
+            Some(_) => (x, y) = (x + dx, y + dy),
+        }
+    }
+    false
+}
+
+/// Equations of up to six numbers, zeros included. About half of the targets
+/// are made by applying random operators, so they can be solved.
+pub fn day7(rng: &mut Rng) -> String {
+    (0..rng.range(1, 10))
+        .map(|_| {
+            let numbers = (0..rng.range(1, 6)).map(|_| rng.range(0, 20)).collect_vec();
+            let target = if rng.chance(50) {
+                numbers[1..]
+                    .iter()
+                    .fold(numbers[0], |acc, &n| match rng.range(0, 2) {
+                        0 => acc + n,
+                        1 => acc * n,
+                        _ => format!("{acc}{n}").parse().unwrap(),
+                    })
+            } else {
+                rng.range(0, 10_000)
+            };
+            format!("{target}: {}\n", numbers.iter().join(" "))
+        })
+        .collect()
+}
+
+/// A map with a few antennas on a handful of frequencies.
+pub fn day8(rng: &mut Rng) -> String {
+    let density = rng.range(3, 15);
+    grid(rng.range(1, 15), rng.range(1, 15), |_, _| {
+        match rng.chance(density) {
+            true => rng.pick(&['0', 'a', 'A', 'z']),
+            false => '.',
+        }
+    })
+}
+
+/// A disk map. Files are never empty, free space often is.
+pub fn day9(rng: &mut Rng) -> String {
+    let files = rng.range(1, 20);
+    let mut disk_map = String::new();
+    for i in 0..files {
+        write!(disk_map, "{}", rng.range(1, 9)).unwrap();
+        if i + 1 < files {
+            write!(disk_map, "{}", rng.range(0, 9)).unwrap();
+        }
+    }
+    disk_map + "\n"
+}
+
+/// A height map that mostly climbs to the south east, so there are plenty of
+/// trails to find.
+pub fn day10(rng: &mut Rng) -> String {
+    let offset = rng.range(0, 9);
+    grid(rng.range(1, 12), rng.range(1, 12), |x, y| {
+        let height = match rng.chance(70) {
+            true => (x + y + offset) % 10,
+            false => rng.range(0, 9),
+        };
+        char::from_digit(height as u32, 10).unwrap()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{days::DAYS, Part};
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        assert_ne!(rng.next_u64(), rng.next_u64());
+        assert!((0..1000).all(|_| (3..=5).contains(&rng.range(3, 5))));
+        assert_eq!(Rng::new(7).next_u64(), Rng::new(7).next_u64());
+    }
+
+    #[test]
+    fn test_inputs_run() {
+        for runner in DAYS {
+            for seed in 0..50 {
+                let mut rng = Rng::new(seed);
+                let input = input(runner.day(), &mut rng).unwrap();
+                if let Err(e) = runner.run(&input, &Part::BOTH) {
+                    panic!("seed {seed}: {e}\n{input}");
+                }
+            }
+        }
+    }
+}
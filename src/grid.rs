@@ -0,0 +1,406 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Index, IndexMut},
+    str::FromStr,
+};
+
+use itertools::Itertools;
+
+use crate::ParseError;
+
+/// A position on (or off) a grid. Coordinates are signed so that stepping off
+/// the edge gives a point that simply isn't in bounds, rather than wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const fn new(x: isize, y: isize) -> Point {
+        Point { x, y }
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Self {
+        Point::new(x as isize, y as isize)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+/// A step between neighbouring cells. `y` grows downwards, as it does when
+/// reading a grid from text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Direction {
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Direction {
+    pub const N: Direction = Direction::new(0, -1);
+    pub const NE: Direction = Direction::new(1, -1);
+    pub const E: Direction = Direction::new(1, 0);
+    pub const SE: Direction = Direction::new(1, 1);
+    pub const S: Direction = Direction::new(0, 1);
+    pub const SW: Direction = Direction::new(-1, 1);
+    pub const W: Direction = Direction::new(-1, 0);
+    pub const NW: Direction = Direction::new(-1, -1);
+
+    /// The four orthogonal directions, clockwise from north
+    pub const ORTHOGONAL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+
+    /// All eight directions, clockwise from north
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    pub const fn new(dx: isize, dy: isize) -> Direction {
+        Direction { dx, dy }
+    }
+
+    /// A quarter turn clockwise
+    pub const fn turn_right(self) -> Direction {
+        Direction::new(-self.dy, self.dx)
+    }
+
+    pub const fn reverse(self) -> Direction {
+        Direction::new(-self.dx, -self.dy)
+    }
+}
+
+/// Compass names, e.g. `N` or `se`
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Direction, String> {
+        const NAMES: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+        NAMES
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(s.trim()))
+            .map(|i| Direction::ALL[i])
+            .ok_or_else(|| format!("expected a compass direction like `N` or `SE`, got `{s}`"))
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Direction) -> Point {
+        Point::new(self.x + rhs.dx, self.y + rhs.dy)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from cells in row-major order.
+    ///
+    /// Panics if `cells` doesn't hold exactly `width * height` cells.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height);
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Reads a grid from a character map, one row per line, converting each
+    /// character with `f`. Characters `f` rejects, and rows of the wrong
+    /// length, are reported as a [`ParseError`].
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        let lines = input.lines().collect_vec();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for line in &lines {
+            for (i, c) in line.char_indices() {
+                let cell = f(c).ok_or_else(|| {
+                    ParseError::at(
+                        input,
+                        &line[i..i + c.len_utf8()],
+                        format!("unexpected `{c}`"),
+                    )
+                })?;
+                cells.push(cell);
+            }
+            let len = line.chars().count();
+            if len != width {
+                let token = line
+                    .char_indices()
+                    .nth(width)
+                    .map_or(&line[line.len()..], |(i, _)| &line[i..]);
+                return Err(ParseError::at(
+                    input,
+                    token,
+                    format!("expected {width} columns, found {len}"),
+                ));
+            }
+        }
+
+        Ok(Grid {
+            cells,
+            width,
+            height: lines.len(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn in_bounds(&self, point: Point) -> bool {
+        (0..self.width as isize).contains(&point.x) && (0..self.height as isize).contains(&point.y)
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.in_bounds(point)
+            .then(|| point.x as usize + point.y as usize * self.width)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    /// Replaces the cell at `point`, returning the old value, or `None` (and
+    /// changing nothing) if the point is out of bounds.
+    pub fn set(&mut self, point: Point, value: T) -> Option<T> {
+        self.get_mut(point)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// Every point on the grid, in row-major order
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Point::from((i % width, i / width)))
+    }
+
+    pub fn enumerate(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// Point positions, in bounds, in the given directions from `point`
+    pub fn neighbours<'a>(
+        &'a self,
+        point: Point,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = Point> + 'a {
+        directions
+            .iter()
+            .map(move |&direction| point + direction)
+            .filter(|&nbr| self.in_bounds(nbr))
+    }
+
+    /// The in-bounds orthogonal neighbours of `point`
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &Direction::ORTHOGONAL)
+    }
+
+    /// The in-bounds orthogonal and diagonal neighbours of `point`
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &Direction::ALL)
+    }
+
+    /// Points from `from` (inclusive) stepping in `direction` until the edge
+    pub fn ray(&self, from: Point, direction: Direction) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(Some(from), move |&point| Some(point + direction))
+            .take_while(|&point| self.in_bounds(point))
+    }
+
+    /// Each row, left to right
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = Point> + '_> + '_ {
+        (0..self.height).map(|y| self.ray(Point::from((0, y)), Direction::E))
+    }
+
+    /// Each column, top to bottom
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = Point> + '_> + '_ {
+        (0..self.width).map(|x| self.ray(Point::from((x, 0)), Direction::S))
+    }
+
+    /// Each `\` diagonal, running down and to the right, starting from the
+    /// bottom left corner
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = Point> + '_> + '_ {
+        let starts = (1..self.height)
+            .rev()
+            .map(|y| Point::from((0, y)))
+            .chain((0..self.width).map(|x| Point::from((x, 0))));
+        starts.map(|start| self.ray(start, Direction::SE))
+    }
+
+    /// Each `/` diagonal, running down and to the left, starting from the
+    /// top left corner
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = Point> + '_> + '_ {
+        let starts = (0..self.width)
+            .map(|x| Point::from((x, 0)))
+            .chain((1..self.height).map(|y| Point::from((self.width.saturating_sub(1), y))));
+        starts.map(|start| self.ray(start, Direction::SW))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+/// Panics if `point` is out of bounds; use [`Grid::get`] for a checked lookup.
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point} is outside the {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point} is outside the {width}x{height} grid"))
+    }
+}
+
+impl<T> Display for Grid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "+{}+", "-".repeat(self.width))?;
+        for line in self.cells.chunks(self.width.max(1)) {
+            write!(f, "|")?;
+            for cell in line {
+                write!(f, "{cell}")?
+            }
+            writeln!(f, "|")?;
+        }
+        writeln!(f, "+{}+", "-".repeat(self.width))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "abc
+def";
+
+    fn collect(
+        lines: impl Iterator<Item = impl Iterator<Item = Point>>,
+        grid: &Grid<char>,
+    ) -> Vec<String> {
+        lines
+            .map(|line| line.map(|point| grid[point]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(INPUT, Some).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&'f'));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Grid::parse("abc\nde\n", Some).unwrap_err();
+        assert_eq!(error.message, "expected 3 columns, found 2");
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = Grid::parse("abc\ndefg", Some).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+
+        let error = Grid::parse("12\n3x", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(error.message, "unexpected `x`");
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
+    #[test]
+    fn test_set() {
+        let mut grid = Grid::parse(INPUT, Some).unwrap();
+        assert_eq!(grid.set(Point::new(1, 1), 'x'), Some('e'));
+        assert_eq!(grid.set(Point::new(1, 2), 'x'), None);
+        assert_eq!(grid[Point::new(1, 1)], 'x');
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::parse(INPUT, Some).unwrap();
+        let corner = Point::new(0, 0);
+        assert_eq!(grid.neighbours4(corner).count(), 2);
+        assert_eq!(grid.neighbours8(corner).count(), 3);
+        assert_eq!(grid.neighbours8(Point::new(1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn test_rays() {
+        let grid = Grid::parse(INPUT, Some).unwrap();
+        assert_eq!(collect(grid.rows(), &grid), ["abc", "def"]);
+        assert_eq!(collect(grid.columns(), &grid), ["ad", "be", "cf"]);
+        assert_eq!(collect(grid.diagonals(), &grid), ["d", "ae", "bf", "c"]);
+        assert_eq!(
+            collect(grid.anti_diagonals(), &grid),
+            ["a", "bd", "ce", "f"]
+        );
+    }
+
+    #[test]
+    fn test_turn_right() {
+        let mut heading = Direction::N;
+        for expected in [Direction::E, Direction::S, Direction::W, Direction::N] {
+            heading = heading.turn_right();
+            assert_eq!(heading, expected);
+        }
+    }
+
+    #[test]
+    fn test_direction_from_str() {
+        assert_eq!("se".parse(), Ok(Direction::SE));
+        assert_eq!(" N ".parse(), Ok(Direction::N));
+        assert!("up".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let grid = Grid::parse(INPUT, Some).unwrap();
+        assert_eq!(grid.to_string(), "+---+\n|abc|\n|def|\n+---+\n");
+    }
+}
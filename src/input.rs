@@ -0,0 +1,309 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u32 = 2024;
+
+/// Somewhere puzzle inputs can be downloaded from.
+pub trait Fetch {
+    fn fetch(&self, day: u32) -> Result<String>;
+}
+
+/// Downloads inputs over HTTP, authenticating with the session cookie.
+///
+/// `base_url` can point at a local server so tests never talk to the real
+/// site.
+pub struct HttpFetcher {
+    pub base_url: String,
+    pub session: String,
+}
+
+impl HttpFetcher {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> HttpFetcher {
+        HttpFetcher {
+            base_url: base_url.into(),
+            session: session.into(),
+        }
+    }
+}
+
+impl Fetch for HttpFetcher {
+    fn fetch(&self, day: u32) -> Result<String> {
+        let url = format!(
+            "{}/{YEAR}/day/{day}/input",
+            self.base_url.trim_end_matches('/')
+        );
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/thepolm3/aoc-2024")
+            .call()
+            .with_context(|| format!("fetching {url}"))?;
+
+        let expected_len = response
+            .header("Content-Length")
+            .and_then(|len| len.parse::<usize>().ok());
+        let body = response
+            .into_string()
+            .with_context(|| format!("reading {url}"))?;
+        if let Some(expected_len) = expected_len {
+            if body.len() != expected_len {
+                bail!(
+                    "truncated download from {url}: got {} of {expected_len} bytes",
+                    body.len()
+                );
+            }
+        }
+        Ok(body)
+    }
+}
+
+/// What we know about a cached input, stored next to it as `dayN.meta`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Seconds since the unix epoch
+    pub fetched_at: u64,
+    pub len: usize,
+    pub checksum: String,
+}
+
+impl CacheEntry {
+    fn new(input: &str) -> CacheEntry {
+        CacheEntry {
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            len: input.len(),
+            checksum: checksum(input),
+        }
+    }
+
+    fn matches(&self, input: &str) -> bool {
+        self.len == input.len() && self.checksum == checksum(input)
+    }
+}
+
+// 64-bit FNV-1a; only needs to catch truncated or corrupted files
+fn checksum(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Why the cache had no usable input for a day.
+#[derive(Debug)]
+pub enum CacheMiss {
+    /// Nothing has been cached for the day
+    Missing,
+    /// The cached input no longer matches its metadata
+    Stale,
+    /// The metadata couldn't be read, so the input can't be trusted
+    Corrupt(anyhow::Error),
+}
+
+impl Display for CacheMiss {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheMiss::Missing => write!(f, "not cached"),
+            CacheMiss::Stale => write!(f, "cached input doesn't match its metadata"),
+            CacheMiss::Corrupt(e) => write!(f, "unreadable cache metadata: {e:#}"),
+        }
+    }
+}
+
+/// Finds puzzle inputs: the cache directory first, then the fetcher (if
+/// there is one), saving whatever it fetches for next time.
+///
+/// Inputs placed in the cache by hand have no metadata and are trusted as
+/// they are. Inputs with metadata that no longer match it are fetched again.
+pub struct InputProvider {
+    cache_dir: PathBuf,
+    fetcher: Option<Box<dyn Fetch>>,
+}
+
+impl InputProvider {
+    pub fn new(cache_dir: impl Into<PathBuf>) -> InputProvider {
+        InputProvider {
+            cache_dir: cache_dir.into(),
+            fetcher: None,
+        }
+    }
+
+    pub fn with_fetcher(self, fetcher: impl Fetch + 'static) -> InputProvider {
+        InputProvider {
+            fetcher: Some(Box::new(fetcher)),
+            ..self
+        }
+    }
+
+    pub fn path(&self, day: u32) -> PathBuf {
+        self.cache_dir.join(format!("day{day}.txt"))
+    }
+
+    fn meta_path(&self, day: u32) -> PathBuf {
+        self.cache_dir.join(format!("day{day}.meta"))
+    }
+
+    /// The cached input for `day`, or why there isn't a usable one. Metadata
+    /// that can't be read is a miss like any other, so the input is fetched
+    /// again.
+    pub fn cached(&self, day: u32) -> Result<String, CacheMiss> {
+        let Ok(input) = std::fs::read_to_string(self.path(day)) else {
+            return Err(CacheMiss::Missing);
+        };
+        match self.entry(day) {
+            Ok(Some(entry)) if !entry.matches(&input) => Err(CacheMiss::Stale),
+            Ok(_) => Ok(input),
+            Err(e) => Err(CacheMiss::Corrupt(e)),
+        }
+    }
+
+    /// Metadata for the cached input for `day`, if it was fetched rather than
+    /// placed by hand
+    pub fn entry(&self, day: u32) -> Result<Option<CacheEntry>> {
+        let path = self.meta_path(day);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let meta = std::fs::read_to_string(&path)?;
+        let entry =
+            serde_json::from_str(&meta).with_context(|| format!("reading {}", path.display()))?;
+        Ok(Some(entry))
+    }
+
+    pub fn get(&self, day: u32) -> Result<String> {
+        self.load(day).map(|(input, _)| input)
+    }
+
+    /// Like [`get`](InputProvider::get), also saying why the input had to be
+    /// fetched rather than read from the cache.
+    pub fn load(&self, day: u32) -> Result<(String, Option<CacheMiss>)> {
+        let miss = match self.cached(day) {
+            Ok(input) => return Ok((input, None)),
+            Err(miss) => miss,
+        };
+        let Some(fetcher) = &self.fetcher else {
+            bail!(
+                "no usable input for day {day} at {}: {miss} (set AOC_SESSION to download it)",
+                self.path(day).display()
+            );
+        };
+        let input = fetcher.fetch(day)?;
+        self.store(day, &input)?;
+        Ok((input, Some(miss)))
+    }
+
+    fn store(&self, day: u32, input: &str) -> Result<()> {
+        std::fs::create_dir_all(&self.cache_dir)?;
+        // write then rename, so an interrupted write never leaves a partial
+        // file under the real name
+        let tmp = self.cache_dir.join(format!(".day{day}.txt.tmp"));
+        std::fs::write(&tmp, input)?;
+        std::fs::rename(&tmp, self.path(day))?;
+        write_json(&self.meta_path(day), &CacheEntry::new(input))
+    }
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::test_util::{serve_once, temp_dir};
+
+    struct Stub {
+        calls: Rc<Cell<u32>>,
+    }
+
+    impl Fetch for Stub {
+        fn fetch(&self, day: u32) -> Result<String> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(format!("input for day {day}\n"))
+        }
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = temp_dir("cache");
+        let calls = Rc::new(Cell::new(0));
+        let provider = InputProvider::new(&dir).with_fetcher(Stub {
+            calls: calls.clone(),
+        });
+
+        assert_eq!(provider.get(3).unwrap(), "input for day 3\n");
+        assert_eq!(provider.get(3).unwrap(), "input for day 3\n");
+        assert_eq!(calls.get(), 1);
+
+        let entry = provider.entry(3).unwrap().unwrap();
+        assert_eq!(entry.len, 16);
+        assert!(entry.fetched_at > 0);
+
+        // a truncated cache file is fetched again
+        std::fs::write(provider.path(3), "input for").unwrap();
+        assert_eq!(provider.get(3).unwrap(), "input for day 3\n");
+        assert_eq!(calls.get(), 2);
+
+        // so is one with corrupt metadata, saying why
+        std::fs::write(provider.meta_path(3), "{not json").unwrap();
+        assert!(matches!(provider.cached(3), Err(CacheMiss::Corrupt(_))));
+        let (input, miss) = provider.load(3).unwrap();
+        assert_eq!(input, "input for day 3\n");
+        assert!(matches!(miss, Some(CacheMiss::Corrupt(_))));
+        assert_eq!(calls.get(), 3);
+        assert!(provider.entry(3).unwrap().is_some());
+        assert!(matches!(provider.load(3).unwrap(), (_, None)));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_hand_placed_input() {
+        let dir = temp_dir("hand-placed");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day1.txt"), "3   4\n").unwrap();
+
+        let provider = InputProvider::new(&dir);
+        assert_eq!(provider.get(1).unwrap(), "3   4\n");
+        assert!(provider.entry(1).unwrap().is_none());
+        assert!(matches!(provider.cached(2), Err(CacheMiss::Missing)));
+        assert!(provider.get(2).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_http_fetcher() {
+        let (url, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n1 2 3\n");
+        let input = HttpFetcher::new(url, "abc123").fetch(7).unwrap();
+        assert_eq!(input, "1 2 3\n");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2024/day/7/input HTTP/1.1"));
+        assert!(request.contains("session=abc123"));
+    }
+
+    #[test]
+    fn test_http_fetcher_truncated() {
+        let (url, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 60\r\n\r\n1 2 3\n");
+        assert!(HttpFetcher::new(url, "abc123").fetch(7).is_err());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_http_fetcher_error_status() {
+        let (url, server) = serve_once("HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n");
+        assert!(HttpFetcher::new(url, "expired").fetch(1).is_err());
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt::Display;
+
+pub mod ansi;
+pub mod bench;
+pub mod days;
+pub mod diagnostics;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod options;
+pub mod parse;
+pub mod runner;
+pub mod submit;
+
+#[cfg(test)]
+mod test_util;
+
+pub use options::Options;
+pub use parse::ParseError;
+pub use runner::{Part, Runner};
+
+/// A single day's puzzle: how to read the input, and how to answer both parts.
+///
+/// Parts take the parsed input by reference so the same input can be reused
+/// for both parts (and by any tooling that wants to run them repeatedly).
+pub trait Solution {
+    const DAY: u32;
+
+    type Input;
+    type Output1: Display;
+    type Output2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Parses with day-specific settings, for days that have any.
+    fn parse_with(input: &str, _options: &Options) -> anyhow::Result<Self::Input> {
+        Ok(Self::parse(input)?)
+    }
+    fn part1(input: &Self::Input) -> Self::Output1;
+    fn part2(input: &Self::Input) -> Self::Output2;
+
+    /// A report on how the answers came about, for days that have one.
+    fn explain(_input: &Self::Input, _options: &Options) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+}
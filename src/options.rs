@@ -0,0 +1,146 @@
+use std::{collections::BTreeMap, fmt::Display, path::Path, str::FromStr};
+
+use anyhow::{anyhow, Context, Result};
+
+/// Day-specific settings as `key=value` pairs, e.g. `format=csv`, passed to
+/// [`Solution::parse_with`](crate::Solution::parse_with) and
+/// [`Solution::explain`](crate::Solution::explain). Each day reads the keys
+/// it knows about and ignores the rest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    values: BTreeMap<String, String>,
+}
+
+impl Options {
+    pub fn new() -> Options {
+        Options::default()
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Options {
+        self.set(key, value);
+        self
+    }
+
+    /// These options, with any set in `other` taking precedence.
+    pub fn merge(mut self, other: Options) -> Options {
+        self.values.extend(other.values);
+        self
+    }
+
+    /// Reads the `[dayN]` table from a TOML config file, e.g.
+    ///
+    /// ```toml
+    /// [day2]
+    /// max_step = 4
+    /// trend = "increasing"
+    /// ```
+    pub fn load(path: &Path, day: u32) -> Result<Options> {
+        let config =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Options::from_toml(&config, day).with_context(|| format!("reading {}", path.display()))
+    }
+
+    fn from_toml(config: &str, day: u32) -> Result<Options> {
+        let mut config = config.parse::<toml::Table>()?;
+        let Some(table) = config.remove(&format!("day{day}")) else {
+            return Ok(Options::new());
+        };
+        let toml::Value::Table(table) = table else {
+            return Err(anyhow!("`day{day}` should be a table"));
+        };
+        Ok(table
+            .into_iter()
+            .map(|(key, value)| match value {
+                toml::Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            })
+            .collect())
+    }
+
+    /// Splits `key=value`, for command line arguments.
+    pub fn parse_pair(s: &str) -> Result<(String, String), String> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected `key=value`, got `{s}`"))?;
+        Ok((key.trim().to_owned(), value.trim().to_owned()))
+    }
+
+    pub fn get<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.values
+            .get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e| anyhow!("option `{key}`: {e} (got `{value}`)"))
+            })
+            .transpose()
+    }
+
+    pub fn get_or<T>(&self, key: &str, default: T) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+}
+
+impl FromIterator<(String, String)> for Options {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Options {
+        Options {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let options = Options::new().with("top", "3").with("format", "csv");
+        assert_eq!(options.get::<usize>("top").unwrap(), Some(3));
+        assert_eq!(options.get_or("missing", 7).unwrap(), 7);
+        assert_eq!(options.get_or("format", String::new()).unwrap(), "csv");
+
+        let error = options.get::<usize>("format").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "option `format`: invalid digit found in string (got `csv`)"
+        );
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let config =
+            "[day2]\nmax_step = 4\ntrend = \"increasing\"\nplateaus = true\n[day1]\ntop = 3\n";
+        let options = Options::from_toml(config, 2).unwrap();
+        assert_eq!(
+            options,
+            Options::new()
+                .with("max_step", "4")
+                .with("trend", "increasing")
+                .with("plateaus", "true")
+        );
+        assert_eq!(Options::from_toml(config, 5).unwrap(), Options::new());
+        assert!(Options::from_toml("day2 = 3", 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_pair() {
+        assert_eq!(
+            Options::parse_pair("top = 3"),
+            Ok(("top".to_owned(), "3".to_owned()))
+        );
+        assert!(Options::parse_pair("top").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use std::{fmt::Display, str::FromStr};
+
+use nom::IResult;
+
+/// Why an input couldn't be parsed, and where.
+///
+/// Lines and columns are 1-based, and columns count characters rather than
+/// bytes. `snippet` is the whole offending line, so the error can be shown
+/// with the bad part underlined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u32>,
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+    /// How many characters of `snippet`, from `column`, are at fault
+    pub len: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error covering `len` bytes of `input` starting at byte `offset`.
+    pub fn new(input: &str, offset: usize, len: usize, message: impl Into<String>) -> ParseError {
+        let offset = offset.min(input.len());
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        let snippet = input[line_start..line_end].trim_end_matches('\r');
+        let end = (offset + len).min(line_end);
+
+        ParseError {
+            day: None,
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            snippet: snippet.to_owned(),
+            len: input
+                .get(offset..end)
+                .map_or(1, |s| s.chars().count().max(1)),
+            message: message.into(),
+        }
+    }
+
+    /// An error pointing at `token`, which must be a slice of `input`.
+    pub fn at(input: &str, token: &str, message: impl Into<String>) -> ParseError {
+        let offset = token.as_ptr() as usize - input.as_ptr() as usize;
+        debug_assert!(offset + token.len() <= input.len());
+        ParseError::new(input, offset, token.len(), message)
+    }
+
+    /// Moves the error to line `line`, for errors found in one line of a
+    /// larger input that is read a line at a time.
+    pub fn on_line(self, line: usize) -> ParseError {
+        ParseError { line, ..self }
+    }
+
+    pub fn with_day(self, day: u32) -> ParseError {
+        ParseError {
+            day: Some(day),
+            ..self
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}: ")?;
+        }
+        writeln!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )?;
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.len)
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `token` (a slice of `input`) as a number.
+pub fn number<T: FromStr>(input: &str, token: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::at(input, token, format!("expected a number, found `{token}`")))
+}
+
+/// Turns the result of a nom parser run over the whole of `input` into a
+/// [`ParseError`]. Anything left over other than a final newline is an error.
+pub fn finish<T>(input: &str, result: IResult<&str, T>) -> Result<T, ParseError> {
+    match result {
+        Ok((rest, value)) if rest.is_empty() || rest == "\n" || rest == "\r\n" => Ok(value),
+        Ok((rest, _)) => Err(leftover(input, rest)),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(unexpected(input, e.input)),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new(
+            input,
+            input.len(),
+            0,
+            "unexpected end of input",
+        )),
+    }
+}
+
+fn unexpected(input: &str, rest: &str) -> ParseError {
+    match rest.chars().next() {
+        None => ParseError::at(input, rest, "unexpected end of input"),
+        Some('\n' | '\r') => ParseError::at(input, &rest[..0], "unexpected end of line"),
+        Some(c) => ParseError::at(input, &rest[..c.len_utf8()], format!("unexpected `{c}`")),
+    }
+}
+
+fn leftover(input: &str, rest: &str) -> ParseError {
+    // a list that stopped early leaves its separating newline behind, but the
+    // problem is on the line after it
+    let rest = rest
+        .strip_prefix("\r\n")
+        .or_else(|| rest.strip_prefix('\n'))
+        .unwrap_or(rest);
+    match rest.chars().next() {
+        Some('\n' | '\r') => ParseError::at(input, &rest[..0], "unexpected blank line"),
+        _ => unexpected(input, rest),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "1 2\n3 x\n";
+
+    #[test]
+    fn test_position() {
+        let error = ParseError::at(INPUT, &INPUT[6..7], "bad");
+        assert_eq!((error.line, error.column, error.len), (2, 3, 1));
+        assert_eq!(error.snippet, "3 x");
+    }
+
+    #[test]
+    fn test_number() {
+        assert_eq!(number::<u32>(INPUT, &INPUT[2..3]), Ok(2));
+        let error = number::<u32>(INPUT, &INPUT[6..7]).unwrap_err();
+        assert_eq!(error.message, "expected a number, found `x`");
+    }
+
+    #[test]
+    fn test_display() {
+        let error = ParseError::at(INPUT, &INPUT[6..7], "expected a number").with_day(1);
+        assert_eq!(
+            error.to_string(),
+            "day 1: expected a number at line 2, column 3
+  |
+2 | 3 x
+  |   ^"
+        );
+    }
+
+    #[test]
+    fn test_finish() {
+        let digits =
+            |input| nom::character::complete::digit1::<&str, nom::error::Error<&str>>(input);
+        assert_eq!(finish("12\n", digits("12\n")), Ok("12"));
+
+        let error = finish("12\n\n", digits("12\n\n")).unwrap_err();
+        assert_eq!(error.message, "unexpected blank line");
+        assert_eq!((error.line, error.column), (2, 1));
+
+        let error = finish("12a", digits("12a")).unwrap_err();
+        assert_eq!(error.message, "unexpected `a`");
+        assert_eq!((error.line, error.column), (1, 3));
+    }
+}
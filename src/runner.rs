@@ -0,0 +1,303 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    bench::{measure, DayBench, Phase},
+    diagnostics, Options, ParseError, Solution,
+};
+
+/// Which half of a puzzle. Serialized as the number `1` or `2`, matching
+/// the site's `level`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl From<Part> for u8 {
+    fn from(part: Part) -> u8 {
+        match part {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = String;
+
+    fn try_from(part: u8) -> Result<Part, String> {
+        match part {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(format!("expected part 1 or 2, got {part}")),
+        }
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Answer {
+    pub part: Part,
+    pub answer: String,
+    pub elapsed: Duration,
+    /// Anything the part [noted](diagnostics::note) along the way
+    pub diagnostics: Vec<Value>,
+}
+
+/// One answer as a flat record, for machine-readable output. Times are in
+/// nanoseconds, as in saved bench reports.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record<'a> {
+    pub day: u32,
+    pub part: Part,
+    pub answer: &'a str,
+    pub parse_ns: u64,
+    pub elapsed_ns: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<&'a [Value]>,
+}
+
+/// The outcome of running one day against one input.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub day: u32,
+    pub parse: Duration,
+    pub answers: Vec<Answer>,
+}
+
+impl Run {
+    pub fn answer(&self, part: Part) -> Option<&Answer> {
+        self.answers.iter().find(|answer| answer.part == part)
+    }
+
+    /// A [`Record`] per answer, with diagnostics only if asked for.
+    pub fn records(&self, diagnostics: bool) -> impl Iterator<Item = Record<'_>> {
+        self.answers.iter().map(move |answer| Record {
+            day: self.day,
+            part: answer.part,
+            answer: &answer.answer,
+            parse_ns: self.parse.as_nanos() as u64,
+            elapsed_ns: answer.elapsed.as_nanos() as u64,
+            diagnostics: diagnostics.then_some(answer.diagnostics.as_slice()),
+        })
+    }
+}
+
+/// Object-safe view of a [`Solution`], so days can be kept in a registry and
+/// picked at runtime.
+pub trait Runner: Sync {
+    fn day(&self) -> u32;
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Run, ParseError>;
+    /// Like [`run`](Runner::run), with day-specific settings, and collecting
+    /// each part's [diagnostics] if `diagnostics` is set.
+    fn run_with(
+        &self,
+        input: &str,
+        parts: &[Part],
+        options: &Options,
+        diagnostics: bool,
+    ) -> anyhow::Result<Run>;
+    /// Times parsing and each part separately, `iterations` times apiece,
+    /// with the same settings as [`run_with`](Runner::run_with).
+    fn bench(&self, input: &str, options: &Options, iterations: usize) -> anyhow::Result<DayBench>;
+    /// See [`Solution::explain`].
+    fn explain(&self, input: &str, options: &Options) -> anyhow::Result<Option<String>>;
+}
+
+impl<S> Runner for S
+where
+    S: Solution + Sync,
+{
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Run, ParseError> {
+        let start = Instant::now();
+        let input = S::parse(input).map_err(|e| e.with_day(S::DAY))?;
+        Ok(answer::<S>(&input, start.elapsed(), parts, false))
+    }
+
+    fn run_with(
+        &self,
+        input: &str,
+        parts: &[Part],
+        options: &Options,
+        diagnostics: bool,
+    ) -> anyhow::Result<Run> {
+        let start = Instant::now();
+        let input = S::parse_with(input, options).map_err(with_day::<S>)?;
+        Ok(answer::<S>(&input, start.elapsed(), parts, diagnostics))
+    }
+
+    fn bench(&self, input: &str, options: &Options, iterations: usize) -> anyhow::Result<DayBench> {
+        let parsed = S::parse_with(input, options).map_err(with_day::<S>)?;
+        let parse = measure(Phase::Parse, iterations, || S::parse_with(input, options));
+        let part1 = measure(Phase::Part1, iterations, || S::part1(&parsed));
+        let part2 = measure(Phase::Part2, iterations, || S::part2(&parsed));
+
+        Ok(DayBench {
+            day: S::DAY,
+            phases: vec![parse, part1, part2],
+        })
+    }
+
+    fn explain(&self, input: &str, options: &Options) -> anyhow::Result<Option<String>> {
+        let input = S::parse_with(input, options).map_err(with_day::<S>)?;
+        S::explain(&input, options)
+    }
+}
+
+fn answer<S: Solution>(
+    input: &S::Input,
+    parse: Duration,
+    parts: &[Part],
+    diagnostics: bool,
+) -> Run {
+    let timed = |part| {
+        let start = Instant::now();
+        let answer = match part {
+            Part::One => S::part1(input).to_string(),
+            Part::Two => S::part2(input).to_string(),
+        };
+        (answer, start.elapsed())
+    };
+    // notes are only built while collecting, so don't unless asked to
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let ((answer, elapsed), diagnostics) = match diagnostics {
+                true => diagnostics::collect(|| timed(part)),
+                false => (timed(part), Vec::new()),
+            };
+            Answer {
+                part,
+                answer,
+                elapsed,
+                diagnostics,
+            }
+        })
+        .collect();
+
+    Run {
+        day: S::DAY,
+        parse,
+        answers,
+    }
+}
+
+fn with_day<S: Solution>(error: anyhow::Error) -> anyhow::Error {
+    match error.downcast::<ParseError>() {
+        Ok(error) => error.with_day(S::DAY).into(),
+        Err(error) => error.context(format!("day {}", S::DAY)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::days;
+
+    #[test]
+    fn test_registry() {
+        let days = days::DAYS
+            .iter()
+            .map(|runner| runner.day())
+            .collect::<Vec<_>>();
+        assert_eq!(days, (1..=10).collect::<Vec<_>>());
+        assert!(days::get(11).is_none());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = days::get(1)
+            .unwrap()
+            .run("3 4\n4 x\n", &Part::BOTH)
+            .unwrap_err();
+        assert_eq!(error.day, Some(1));
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+
+    #[test]
+    fn test_run() {
+        let run = days::get(1)
+            .unwrap()
+            .run("3 4\n4 3\n2 5\n1 3\n3 9\n3 3", &[Part::Two])
+            .unwrap();
+        assert_eq!(run.day, 1);
+        assert!(run.answer(Part::One).is_none());
+        assert_eq!(run.answer(Part::Two).unwrap().answer, "31");
+    }
+
+    #[test]
+    fn test_run_with() {
+        let runner = days::get(2).unwrap();
+        let input = "1 2 3\n1 1 2\n";
+        let strict = runner.run(input, &[Part::One]).unwrap();
+        assert_eq!(strict.answers[0].answer, "1");
+
+        let plateaus = Options::new().with("plateaus", "true");
+        let run = runner
+            .run_with(input, &[Part::One], &plateaus, false)
+            .unwrap();
+        assert_eq!(run.answers[0].answer, "2");
+
+        let error = runner
+            .run_with("1 x\n", &Part::BOTH, &plateaus, false)
+            .unwrap_err();
+        assert_eq!(error.downcast::<ParseError>().unwrap().day, Some(2));
+        let bad = Options::new().with("plateaus", "maybe");
+        let error = runner
+            .run_with(input, &Part::BOTH, &bad, false)
+            .unwrap_err();
+        assert!(format!("{error:#}").starts_with("day 2: option `plateaus`"));
+        let error = runner.bench(input, &bad, 1).unwrap_err();
+        assert!(format!("{error:#}").starts_with("day 2: option `plateaus`"));
+        let bench = runner.bench(input, &plateaus, 1).unwrap();
+        assert_eq!(bench.phases.len(), 3);
+    }
+
+    #[test]
+    fn test_records() {
+        let runner = days::get(7).unwrap();
+        let input = "190: 10 19\n83: 17 5\n";
+        let quiet = runner.run(input, &[Part::One]).unwrap();
+        assert!(quiet.answers[0].diagnostics.is_empty());
+
+        let run = runner
+            .run_with(input, &[Part::One], &Options::new(), true)
+            .unwrap();
+        let record = run.records(true).next().unwrap();
+        assert_eq!(
+            (record.day, record.part, record.answer),
+            (7, Part::One, "190")
+        );
+        assert_eq!(
+            serde_json::to_value(record.diagnostics).unwrap(),
+            serde_json::json!([
+                { "target": 190, "numbers": [10, 19], "solvable": true },
+                { "target": 83, "numbers": [17, 5], "solvable": false },
+            ])
+        );
+
+        let json = serde_json::to_string(&run.records(false).next().unwrap()).unwrap();
+        assert!(json.starts_with(r#"{"day":7,"part":1,"answer":"190","parse_ns":"#));
+        assert!(!json.contains("diagnostics"));
+    }
+}
@@ -0,0 +1,378 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{input::YEAR, Part};
+
+/// Somewhere answers can be sent. Returns the raw response body, which
+/// [`parse_response`] turns into a [`Verdict`].
+pub trait Submit {
+    fn submit(&self, day: u32, part: Part, answer: &str) -> Result<String>;
+}
+
+/// Posts answers to the site (or anything that looks like it, for tests).
+pub struct HttpSubmitter {
+    pub base_url: String,
+    pub session: String,
+}
+
+impl HttpSubmitter {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> HttpSubmitter {
+        HttpSubmitter {
+            base_url: base_url.into(),
+            session: session.into(),
+        }
+    }
+}
+
+impl Submit for HttpSubmitter {
+    fn submit(&self, day: u32, part: Part, answer: &str) -> Result<String> {
+        let url = format!(
+            "{}/{YEAR}/day/{day}/answer",
+            self.base_url.trim_end_matches('/')
+        );
+        let level = part.to_string();
+        let response = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/thepolm3/aoc-2024")
+            .send_form(&[("level", &level), ("answer", answer)])
+            .with_context(|| format!("posting to {url}"))?;
+        Ok(response.into_string()?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, with no hint as to which way
+    Wrong,
+    /// Submitted too soon after the last guess; nothing was checked
+    TooSoon,
+    /// The part has already been solved
+    AlreadySolved,
+    Unknown,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooSoon => write!(f, "too soon"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::Unknown => write!(f, "unrecognised response"),
+        }
+    }
+}
+
+/// What the site said about an answer: the verdict, and how many seconds to
+/// wait before guessing again (if it said).
+pub fn parse_response(body: &str) -> (Verdict, Option<u64>) {
+    let verdict = if body.contains("That's the right answer") {
+        Verdict::Correct
+    } else if body.contains("That's not the right answer") {
+        if body.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if body.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if body.contains("You gave an answer too recently") {
+        Verdict::TooSoon
+    } else if body.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown
+    };
+    (verdict, parse_wait(body))
+}
+
+fn parse_wait(body: &str) -> Option<u64> {
+    let left = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+    if let Some(captures) = left.captures(body) {
+        let minutes = captures.get(1).map_or(0, |m| m.as_str().parse().unwrap());
+        let seconds: u64 = captures[2].parse().unwrap();
+        return Some(minutes * 60 + seconds);
+    }
+    let minutes = Regex::new(r"(?i)please wait (one|\d+) minutes? before trying again").unwrap();
+    minutes.captures(body).map(|captures| match &captures[1] {
+        "one" => 60,
+        n => n.parse::<u64>().unwrap() * 60,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guess {
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// Seconds since the unix epoch
+    pub at: u64,
+    /// No guess should be sent before this time
+    pub retry_after: Option<u64>,
+}
+
+/// Why an answer was not sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    AlreadySolved { answer: String },
+    KnownWrong,
+    AtOrAbove { too_high: i128 },
+    AtOrBelow { too_low: i128 },
+    Wait { seconds: u64 },
+}
+
+impl std::fmt::Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Refusal::AlreadySolved { answer } => {
+                write!(f, "already solved, the answer was {answer}")
+            }
+            Refusal::KnownWrong => write!(f, "that answer was already wrong"),
+            Refusal::AtOrAbove { too_high } => write!(f, "{too_high} was already too high"),
+            Refusal::AtOrBelow { too_low } => write!(f, "{too_low} was already too low"),
+            Refusal::Wait { seconds } => write!(f, "wait {seconds}s before guessing again"),
+        }
+    }
+}
+
+impl std::error::Error for Refusal {}
+
+/// Every guess made for one day, kept as `dayN.json` in the ledger directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ledger {
+    path: PathBuf,
+    pub guesses: Vec<Guess>,
+}
+
+impl Ledger {
+    pub fn load(dir: &Path, day: u32) -> Result<Ledger> {
+        let path = dir.join(format!("day{day}.json"));
+        let guesses = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("reading {}", path.display()))?,
+            Err(_) => Vec::new(),
+        };
+        Ok(Ledger { path, guesses })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.guesses)?)?;
+        Ok(())
+    }
+
+    fn guesses(&self, part: Part) -> impl Iterator<Item = &Guess> {
+        self.guesses.iter().filter(move |guess| guess.part == part)
+    }
+
+    pub fn correct(&self, part: Part) -> Option<&str> {
+        self.guesses(part)
+            .find(|guess| guess.verdict == Verdict::Correct)
+            .map(|guess| guess.answer.as_str())
+    }
+
+    /// Checks `answer` against everything already learned, without sending it.
+    pub fn check(&self, part: Part, answer: &str, now: u64) -> Result<(), Refusal> {
+        if let Some(correct) = self.correct(part) {
+            return Err(Refusal::AlreadySolved {
+                answer: correct.to_owned(),
+            });
+        }
+        if let Some(retry_after) = self.guesses.iter().filter_map(|g| g.retry_after).max() {
+            if now < retry_after {
+                return Err(Refusal::Wait {
+                    seconds: retry_after - now,
+                });
+            }
+        }
+        let wrong = [Verdict::Wrong, Verdict::TooHigh, Verdict::TooLow];
+        if self
+            .guesses(part)
+            .any(|guess| guess.answer == answer && wrong.contains(&guess.verdict))
+        {
+            return Err(Refusal::KnownWrong);
+        }
+
+        let Ok(value) = answer.parse::<i128>() else {
+            return Ok(());
+        };
+        let bound = |verdict: Verdict| {
+            self.guesses(part)
+                .filter(move |guess| guess.verdict == verdict)
+                .filter_map(|guess| guess.answer.parse::<i128>().ok())
+        };
+        if let Some(too_high) = bound(Verdict::TooHigh).min() {
+            if value >= too_high {
+                return Err(Refusal::AtOrAbove { too_high });
+            }
+        }
+        if let Some(too_low) = bound(Verdict::TooLow).max() {
+            if value <= too_low {
+                return Err(Refusal::AtOrBelow { too_low });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, part: Part, answer: &str, response: &str, now: u64) -> Verdict {
+        let (verdict, wait) = parse_response(response);
+        self.guesses.push(Guess {
+            part,
+            answer: answer.to_owned(),
+            verdict: verdict.clone(),
+            at: now,
+            retry_after: wait.map(|wait| now + wait),
+        });
+        verdict
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Sends `answer` unless the ledger already rules it out, and records what
+/// came back.
+pub fn submit(
+    submitter: &dyn Submit,
+    ledger: &mut Ledger,
+    day: u32,
+    part: Part,
+    answer: &str,
+) -> Result<Verdict> {
+    ledger.check(part, answer, now())?;
+    let response = submitter.submit(day, part, answer)?;
+    let verdict = ledger.record(part, answer, &response, now());
+    ledger.save()?;
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{serve_once, temp_dir};
+
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high. \
+        If you're stuck, make sure you're using the full input data. Please wait one minute \
+        before trying again. [<a href=\"/2024/day/6\">Return to Day 6</a>]</p></article>";
+    const TOO_SOON: &str = "<article><p>You gave an answer too recently; you have to wait \
+        after submitting an answer before trying again.  You have 1m 3s left to wait. \
+        [<a href=\"/2024/day/6\">Return to Day 6</a>]</p></article>";
+    const CORRECT: &str = "<article><p>That's the right answer!  You are one gold star \
+        closer to finding the Chief Historian.</p></article>";
+
+    fn ledger(guesses: &[(Part, &str, &str)]) -> Ledger {
+        let mut ledger = Ledger {
+            path: PathBuf::new(),
+            guesses: Vec::new(),
+        };
+        for (part, answer, response) in guesses {
+            ledger.record(*part, answer, response, 0);
+        }
+        ledger
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(parse_response(TOO_HIGH), (Verdict::TooHigh, Some(60)));
+        assert_eq!(parse_response(TOO_SOON), (Verdict::TooSoon, Some(63)));
+        assert_eq!(parse_response(CORRECT), (Verdict::Correct, None));
+        assert_eq!(
+            parse_response(
+                "That's not the right answer. Please wait 5 minutes before trying again."
+            ),
+            (Verdict::Wrong, Some(300))
+        );
+        assert_eq!(parse_response("<html></html>"), (Verdict::Unknown, None));
+    }
+
+    #[test]
+    fn test_check() {
+        let ledger = ledger(&[
+            (Part::One, "500", TOO_HIGH),
+            (
+                Part::One,
+                "100",
+                "That's not the right answer; your answer is too low.",
+            ),
+            (Part::One, "abc", "That's not the right answer."),
+        ]);
+        assert_eq!(
+            ledger.check(Part::One, "200", 0),
+            Err(Refusal::Wait { seconds: 60 })
+        );
+        assert_eq!(ledger.check(Part::One, "200", 60), Ok(()));
+        assert_eq!(
+            ledger.check(Part::One, "600", 60),
+            Err(Refusal::AtOrAbove { too_high: 500 })
+        );
+        assert_eq!(ledger.check(Part::One, "100", 60), Err(Refusal::KnownWrong));
+        assert_eq!(
+            ledger.check(Part::One, "50", 60),
+            Err(Refusal::AtOrBelow { too_low: 100 })
+        );
+        assert_eq!(ledger.check(Part::One, "abc", 60), Err(Refusal::KnownWrong));
+        assert_eq!(ledger.check(Part::Two, "600", 60), Ok(()));
+    }
+
+    #[test]
+    fn test_already_solved() {
+        let ledger = ledger(&[(Part::Two, "41", CORRECT)]);
+        assert_eq!(
+            ledger.check(Part::Two, "42", 0),
+            Err(Refusal::AlreadySolved {
+                answer: "41".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let dir = temp_dir("ledger");
+        let (url, server) = serve_once(&format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{TOO_HIGH}",
+            TOO_HIGH.len()
+        ));
+        let submitter = HttpSubmitter::new(url, "abc123");
+
+        let mut ledger = Ledger::load(&dir, 6).unwrap();
+        let verdict = submit(&submitter, &mut ledger, 6, Part::Two, "1234").unwrap();
+        assert_eq!(verdict, Verdict::TooHigh);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2024/day/6/answer HTTP/1.1"));
+        assert!(request.ends_with("level=2&answer=1234"));
+
+        // the guess was saved, and the next one is refused without being
+        // sent (there is no server left to answer it)
+        let mut ledger = Ledger::load(&dir, 6).unwrap();
+        assert_eq!(ledger.guesses.len(), 1);
+        let error = submit(&submitter, &mut ledger, 6, Part::Two, "1000").unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Refusal>(),
+            Some(Refusal::Wait { .. })
+        ));
+        assert_eq!(
+            ledger.check(Part::Two, "2000", u64::MAX),
+            Err(Refusal::AtOrAbove { too_high: 1234 })
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
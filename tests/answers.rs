@@ -0,0 +1,90 @@
+//! Runs every registered day against its real input and checks the answers
+//! recorded in `answers.toml`, so a refactor can't quietly change one.
+
+use std::collections::BTreeMap;
+
+use aoc_2024::{days::DAYS, input::InputProvider, Part};
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+struct Answers {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+impl Answers {
+    fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+}
+
+fn recorded() -> BTreeMap<String, Answers> {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+    toml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// Real inputs can't be committed, so days without one are skipped. Every
+/// input found must have both answers recorded, so fetching a new input
+/// fails this until its answers are added.
+#[test]
+fn test_real_inputs() {
+    let cache_dir = std::env::var("AOC_CACHE_DIR")
+        .unwrap_or_else(|_| concat!(env!("CARGO_MANIFEST_DIR"), "/inputs").to_owned());
+    let provider = InputProvider::new(cache_dir);
+    let recorded = recorded();
+
+    let mut failures = Vec::new();
+    for runner in DAYS {
+        let day = runner.day();
+        let input = match provider.cached(day) {
+            Ok(input) => input,
+            Err(miss) => {
+                eprintln!("day {day}: {miss}, skipping");
+                continue;
+            }
+        };
+        let Some(answers) = recorded.get(&format!("day{day}")) else {
+            failures.push(format!("day {day}: input found but no answers recorded"));
+            continue;
+        };
+
+        let run = match runner.run(&input, &Part::BOTH) {
+            Ok(run) => run,
+            Err(e) => {
+                failures.push(format!("{e}"));
+                continue;
+            }
+        };
+        for answer in &run.answers {
+            match answers.get(answer.part) {
+                Some(expected) if expected != answer.answer => failures.push(format!(
+                    "day {day} part {}: expected {expected}, got {}",
+                    answer.part, answer.answer
+                )),
+                Some(_) => {}
+                None => failures.push(format!(
+                    "day {day} part {}: no answer recorded (got {})",
+                    answer.part, answer.answer
+                )),
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_answers_file() {
+    let registered = DAYS
+        .iter()
+        .map(|runner| format!("day{}", runner.day()))
+        .collect::<Vec<_>>();
+    for day in recorded().keys() {
+        assert!(
+            registered.contains(day),
+            "answers.toml: unknown day `{day}`"
+        );
+    }
+}